@@ -0,0 +1,38 @@
+//! Detect whether the crate is built with a nightly compiler.
+//!
+//! On nightly the `nightly` cfg is set and the fast-math intrinsics are used, on stable
+//! `Fast` falls back to regular float operations. The nightly only crate features do nothing
+//! on stable, which gets a warning.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-check-cfg=cfg(nightly)");
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+    if version.contains("-nightly") || version.contains("-dev") {
+        println!("cargo:rustc-cfg=nightly");
+    } else {
+        for (feature, item) in [
+            ("f16", "`FF16`"),
+            ("f128", "`FF128`"),
+            ("simd", "`Fast<Simd>`"),
+        ] {
+            let var = format!("CARGO_FEATURE_{}", feature.to_uppercase());
+            if env::var_os(var).is_some() {
+                println!(
+                    "cargo:warning=the `{}` feature needs a nightly compiler, {} is not available",
+                    feature, item
+                );
+            }
+        }
+    }
+}
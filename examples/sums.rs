@@ -1,23 +1,28 @@
 extern crate fast_floats;
 
-use fast_floats::Fast;
+use fast_floats::{Algebraic, Fast, FF64};
 
-// for demonstration purposes
+/// For demonstration purposes
+///
+/// # Safety
+///
+/// All elements of `xs` must be finite.
 pub unsafe fn fast_sum(xs: &[f64]) -> f64 {
-    *xs.iter()
-        .map(|&x| Fast::new(x))
-        .fold(Fast::new(0.), |acc, x| acc + x)
+    *xs.iter().map(|&x| Fast::new(x)).sum::<FF64>()
 }
 
-// for demonstration purposes
-pub unsafe fn fast_dot(xs: &[f64], ys: &[f64]) -> f64 {
-    *xs.iter().zip(ys).fold(Fast::new(0.), |acc, (&x, &y)| {
-        acc + Fast::new(x) * Fast::new(y)
-    })
+/// For demonstration purposes
+pub fn fast_dot(xs: &[FF64], ys: &[FF64]) -> f64 {
+    *xs.iter().zip(ys).map(|(x, y)| x * y).sum::<FF64>()
+}
+
+/// For demonstration purposes; no unsafe is needed with the algebraic flags
+pub fn algebraic_sum(xs: &[f64]) -> f64 {
+    *xs.iter().sum::<Algebraic<f64>>()
 }
 
 pub fn regular_sum(xs: &[f64]) -> f64 {
-    xs.iter().map(|&x| x).fold(0., |acc, x| acc + x)
+    xs.iter().copied().fold(0., |acc, x| acc + x)
 }
 
 fn main() {}
@@ -0,0 +1,787 @@
+//! Approximate transcendental functions.
+//!
+//! Polynomial approximations of `exp`, `ln`, `log2`, `sin`, `cos`, `tan`, `atan`, `atan2`,
+//! `tanh` and `1 / sqrt` for `Fast<f32>` and `Fast<f64>`, at three accuracy tiers:
+//!
+//! - [`Low`]: about a third of the significand bits
+//! - [`Medium`]: about two thirds of the significand bits
+//! - [`High`]: within a few ULP
+//!
+//! The functions are provided methods of [`Accuracy`], so they are called on the tier:
+//!
+//! ```
+//! use fast_floats::approx::{Accuracy, Medium};
+//! use fast_floats::FF32;
+//!
+//! let y = Medium::exp(FF32::from(1.));
+//! assert!((*y - core::f32::consts::E).abs() < 1e-4);
+//! ```
+//!
+//! Unlike the math functions of `Fast`, these work on stable, and they never produce NaN or
+//! infinite values, even for inputs outside of their domain.
+//!
+//! # Accuracy
+//!
+//! Maximum error in ULP. For `f32` these were measured once over every input in the domains
+//! below (`atan2` on samples), with the ignored test `accuracy_exhaustive`, which takes most of
+//! an hour in release mode. For `f64` they were measured on samples. The regular test suite
+//! only checks them on samples, every 10007th `f32` and 4096 `f64` per function.
+//!
+//! | Function | `f32` [`Low`] | [`Medium`] | [`High`] | `f64` [`Low`] | [`Medium`] | [`High`] |
+//! |----------|--------------:|-----------:|---------:|--------------:|-----------:|---------:|
+//! | `exp`    |          2400 |         85 |        2 |         1.5e9 |      15000 |        2 |
+//! | `ln`     |          1100 |          8 |        3 |         3.5e9 |        700 |        3 |
+//! | `log2`   |          1500 |         12 |        4 |           5e9 |       1000 |        3 |
+//! | `sin`    |          9500 |         80 |        2 |         1.1e9 |       2000 |        2 |
+//! | `cos`    |          9500 |         80 |        2 |         1.1e9 |       2000 |        2 |
+//! | `tan`    |         10500 |        115 |        4 |         1.5e9 |       2700 |        4 |
+//! | `atan`   |          1000 |         35 |        3 |           6e8 |      21000 |        3 |
+//! | `atan2`  |          1000 |         35 |        4 |           6e8 |      21000 |        3 |
+//! | `tanh`   |          2700 |         80 |        2 |         1.2e9 |      28000 |        2 |
+//! | `rsqrt`  |         28400 |         75 |        3 |          4e10 |     250000 |        3 |
+//!
+//! `sincos` has the errors of `sin` and `cos`.
+//!
+//! # Domains
+//!
+//! - `exp`: the input is clamped to the range where the result is a finite normal number.
+//! - `ln`, `log2`, `rsqrt`: positive normal numbers.
+//! - `sin`, `cos`, `sincos`, `tan`: `|x| <= 2^20`. For `f64`, the relative error can be
+//!   larger very close to a multiple of π/2.
+//! - `atan`, `atan2`, `tanh`: all finite values; `atan2(0, 0)` is zero.
+//!
+//! Outside of the domain the results are unspecified, but finite.
+
+use crate::flags::FlagSet;
+use crate::{Fast, FastFloat};
+use std::marker::PhantomData;
+
+/// About a third of the significand bits, see the [module documentation](self).
+pub enum Low {}
+
+/// About two thirds of the significand bits, see the [module documentation](self).
+pub enum Medium {}
+
+/// Within a few ULP, see the [module documentation](self).
+pub enum High {}
+
+#[inline(always)]
+fn fast<F, Flags>(x: F) -> Fast<F, Flags> {
+    Fast(x, PhantomData)
+}
+
+/// The coefficients of a polynomial in the table of the tier
+#[inline(always)]
+fn poly<F: ApproxFloat, Flags: FlagSet>(table: &[F]) -> &[Fast<F, Flags>] {
+    // The coefficients are finite.
+    unsafe { Fast::from_slice(table) }
+}
+
+/// `sin` on `[-π/4, π/4]`
+#[inline(always)]
+fn sin_kernel<A: Accuracy, F: ApproxFloat, Flags: FlagSet>(r: Fast<F, Flags>) -> Fast<F, Flags> {
+    r * (r * r).poly_eval(poly(F::SIN[A::TIER]))
+}
+
+/// `cos` on `[-π/4, π/4]`
+#[inline(always)]
+fn cos_kernel<A: Accuracy, F: ApproxFloat, Flags: FlagSet>(r: Fast<F, Flags>) -> Fast<F, Flags> {
+    (r * r).poly_eval(poly(F::COS[A::TIER]))
+}
+
+/// `ln` of `2^e * m` as `(e, ln(m))`
+#[inline(always)]
+fn ln_parts<A: Accuracy, F: ApproxFloat, Flags: FlagSet>(
+    x: Fast<F, Flags>,
+) -> (Fast<F, Flags>, Fast<F, Flags>) {
+    let (e, m) = x.0.split();
+    // exact, since m is in [√½, √2)
+    let f: Fast<F, Flags> = fast(m - F::ONE);
+    let s = f / (f + F::TWO);
+    (fast(e), s * (s * s).poly_eval(poly(F::LN[A::TIER])))
+}
+
+/// `atan` of a non-negative value
+#[inline(always)]
+fn atan_positive<A: Accuracy, F: ApproxFloat, Flags: FlagSet>(a: Fast<F, Flags>) -> Fast<F, Flags> {
+    let (base, t) = if a.0 > F::TAN_3PI_8 {
+        (F::FRAC_PI_2, -a.recip())
+    } else if a.0 > F::TAN_PI_8 {
+        (F::FRAC_PI_4, (a - F::ONE) / (a + F::ONE))
+    } else {
+        (F::ZERO, a)
+    };
+    t.mul_add((t * t).poly_eval(poly(F::ATAN[A::TIER])), fast(base))
+}
+
+/// An accuracy tier, see the [module documentation](self).
+///
+/// This trait is sealed and can not be implemented outside of this crate.
+pub trait Accuracy: Sized + private::Sealed {
+    /// Index into the coefficient tables
+    #[doc(hidden)]
+    const TIER: usize;
+
+    /// `e^x`
+    #[inline]
+    fn exp<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        let x = if x.0 < F::EXP_MIN {
+            F::EXP_MIN
+        } else if x.0 > F::EXP_MAX {
+            F::EXP_MAX
+        } else {
+            x.0
+        };
+        // x = k ln(2) + r, in strict arithmetic so that the reduction is not reassociated away
+        let (k, kf) = (x * F::LOG2_E).round_int();
+        let r = (x - kf * F::LN_2_HI) - kf * F::LN_2_LO;
+        let p = fast::<_, Flags>(r).poly_eval(poly(F::EXP[Self::TIER]));
+        fast(p.0.scale(k))
+    }
+
+    /// The natural logarithm
+    #[inline]
+    fn ln<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        let (e, ln_m) = ln_parts::<Self, _, _>(x);
+        e.mul_add(fast(F::LN_2), ln_m)
+    }
+
+    /// The base 2 logarithm
+    #[inline]
+    fn log2<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        let (e, ln_m) = ln_parts::<Self, _, _>(x);
+        ln_m.mul_add(fast(F::LOG2_E), e)
+    }
+
+    /// The sine
+    #[inline]
+    fn sin<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        let (k, r) = x.0.reduce_half_pi();
+        let y = if k & 1 == 0 {
+            sin_kernel::<Self, _, _>(fast(r))
+        } else {
+            cos_kernel::<Self, _, _>(fast(r))
+        };
+        if k & 2 == 0 {
+            y
+        } else {
+            -y
+        }
+    }
+
+    /// The cosine
+    #[inline]
+    fn cos<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        let (k, r) = x.0.reduce_half_pi();
+        let y = if k & 1 == 0 {
+            cos_kernel::<Self, _, _>(fast(r))
+        } else {
+            -sin_kernel::<Self, _, _>(fast(r))
+        };
+        if k & 2 == 0 {
+            y
+        } else {
+            -y
+        }
+    }
+
+    /// The sine and the cosine, sharing the range reduction
+    #[inline]
+    fn sincos<F: ApproxFloat, Flags: FlagSet>(
+        x: Fast<F, Flags>,
+    ) -> (Fast<F, Flags>, Fast<F, Flags>) {
+        let (k, r) = x.0.reduce_half_pi();
+        let s = sin_kernel::<Self, _, _>(fast(r));
+        let c = cos_kernel::<Self, _, _>(fast(r));
+        match k & 3 {
+            0 => (s, c),
+            1 => (c, -s),
+            2 => (-s, -c),
+            _ => (-c, s),
+        }
+    }
+
+    /// The tangent
+    #[inline]
+    fn tan<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        let (s, c) = Self::sincos(x);
+        s / c
+    }
+
+    /// The arctangent, in radians
+    #[inline]
+    fn atan<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        atan_positive::<Self, _, _>(x.abs()).copysign(x)
+    }
+
+    /// The four quadrant arctangent of `y / x`, in radians
+    #[inline]
+    fn atan2<F: ApproxFloat, Flags: FlagSet>(
+        y: Fast<F, Flags>,
+        x: Fast<F, Flags>,
+    ) -> Fast<F, Flags> {
+        let (ax, ay) = (x.abs(), y.abs());
+        let (swap, num, den) = if ay.0 > ax.0 {
+            (true, ax, ay)
+        } else {
+            (false, ay, ax)
+        };
+        if den.0 == F::ZERO {
+            return fast(F::ZERO);
+        }
+        let mut t = atan_positive::<Self, _, _>(num / den);
+        if swap {
+            t = fast::<_, Flags>(F::FRAC_PI_2) - t;
+        }
+        if x.0 < F::ZERO {
+            t = fast::<_, Flags>(F::PI) - t;
+        }
+        t.copysign(y)
+    }
+
+    /// The hyperbolic tangent
+    #[inline]
+    fn tanh<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        let a = x.abs();
+        let y = if a.0 < F::TANH_SMALL {
+            a * (a * a).poly_eval(poly(F::TANH[Self::TIER]))
+        } else if a.0 > F::TANH_ONE {
+            fast(F::ONE)
+        } else {
+            fast::<_, Flags>(F::ONE) - fast::<_, Flags>(F::TWO) / (Self::exp(a + a) + F::ONE)
+        };
+        y.copysign(x)
+    }
+
+    /// The reciprocal square root, `1 / sqrt(x)`
+    #[inline]
+    fn rsqrt<F: ApproxFloat, Flags: FlagSet>(x: Fast<F, Flags>) -> Fast<F, Flags> {
+        // Newton iterations from the bit-level estimate
+        let mut y = fast(x.0.rsqrt_estimate());
+        let half_x = x * F::HALF;
+        for _ in 0..F::RSQRT_ITERATIONS[Self::TIER] {
+            y = y * (fast::<_, Flags>(F::THREE_HALVES) - half_x * y * y);
+        }
+        y
+    }
+}
+
+macro_rules! impl_accuracy {
+    ($($tier:ident = $index:expr;)*) => {
+        $(
+        impl private::Sealed for $tier {}
+
+        impl Accuracy for $tier {
+            const TIER: usize = $index;
+        }
+        )*
+    }
+}
+
+impl_accuracy! {
+    Low = 0;
+    Medium = 1;
+    High = 2;
+}
+
+/// The float types with approximations: `f32` and `f64`.
+///
+/// This trait is sealed and can not be implemented outside of this crate.
+pub trait ApproxFloat: FastFloat + 'static {
+    #[doc(hidden)]
+    const EXP: [&'static [Self]; 3];
+    #[doc(hidden)]
+    const LN: [&'static [Self]; 3];
+    #[doc(hidden)]
+    const SIN: [&'static [Self]; 3];
+    #[doc(hidden)]
+    const COS: [&'static [Self]; 3];
+    #[doc(hidden)]
+    const ATAN: [&'static [Self]; 3];
+    #[doc(hidden)]
+    const TANH: [&'static [Self]; 3];
+    #[doc(hidden)]
+    const RSQRT_ITERATIONS: [usize; 3];
+    #[doc(hidden)]
+    const HALF: Self;
+    #[doc(hidden)]
+    const TWO: Self;
+    #[doc(hidden)]
+    const THREE_HALVES: Self;
+    #[doc(hidden)]
+    const EXP_MIN: Self;
+    #[doc(hidden)]
+    const EXP_MAX: Self;
+    #[doc(hidden)]
+    const LN_2_HI: Self;
+    #[doc(hidden)]
+    const LN_2_LO: Self;
+    #[doc(hidden)]
+    const TAN_PI_8: Self;
+    #[doc(hidden)]
+    const TAN_3PI_8: Self;
+    #[doc(hidden)]
+    const TANH_SMALL: Self;
+    #[doc(hidden)]
+    const TANH_ONE: Self;
+    /// Round to the nearest integer, as an integer and as a float
+    #[doc(hidden)]
+    fn round_int(self) -> (i32, Self);
+    /// Multiply a positive normal number by `2^k`
+    #[doc(hidden)]
+    fn scale(self, k: i32) -> Self;
+    /// Split into `e` and `m` in `[√½, √2)` with `self = 2^e * m`
+    #[doc(hidden)]
+    fn split(self) -> (Self, Self);
+    /// Reduce to `k` and `r` in about `[-π/4, π/4]` with `self = k π/2 + r`
+    #[doc(hidden)]
+    fn reduce_half_pi(self) -> (i32, Self);
+    /// Estimate of `1 / sqrt(self)` with a relative error below 4%
+    #[doc(hidden)]
+    fn rsqrt_estimate(self) -> Self;
+}
+
+macro_rules! impl_approx_float {
+    ($($f:ident, $bits:ty, $shift:expr, $sqrt_half:expr, $rsqrt_magic:expr, $reduce:ident;)*) => {
+        $(
+        impl ApproxFloat for $f {
+            const HALF: Self = 0.5;
+            const TWO: Self = 2.;
+            const THREE_HALVES: Self = 1.5;
+            const TAN_PI_8: Self = std::$f::consts::SQRT_2 - 1.;
+            const TAN_3PI_8: Self = std::$f::consts::SQRT_2 + 1.;
+            const TANH_SMALL: Self = 0.55;
+            impl_approx_float!(@$f);
+
+            #[inline(always)]
+            fn round_int(self) -> (i32, Self) {
+                let rounded = (self + $shift) - $shift;
+                (rounded as i32, rounded)
+            }
+
+            #[inline(always)]
+            fn scale(self, k: i32) -> Self {
+                const MANTISSA: u32 = $f::MANTISSA_DIGITS - 1;
+                $f::from_bits(self.to_bits().wrapping_add((k as $bits) << MANTISSA))
+            }
+
+            #[inline(always)]
+            fn split(self) -> (Self, Self) {
+                const MANTISSA: u32 = $f::MANTISSA_DIGITS - 1;
+                const BIAS: i32 = $f::MAX_EXP - 1;
+                const ONE: $bits = (1. as $f).to_bits();
+                let bits = self.to_bits().wrapping_add(ONE - $sqrt_half);
+                let e = (bits >> MANTISSA) as i32 - BIAS;
+                let m = $f::from_bits((bits & ((1 << MANTISSA) - 1)) + $sqrt_half);
+                (e as $f, m)
+            }
+
+            #[inline(always)]
+            fn reduce_half_pi(self) -> (i32, Self) {
+                $reduce(self)
+            }
+
+            #[inline(always)]
+            fn rsqrt_estimate(self) -> Self {
+                $f::from_bits(($rsqrt_magic as $bits).wrapping_sub(self.to_bits() >> 1))
+            }
+        }
+        )*
+    };
+    (@f32) => {
+        const EXP_MIN: Self = -87.33;
+        const EXP_MAX: Self = 88.72;
+        const LN_2_HI: Self = 0.69314575;
+        const LN_2_LO: Self = 1.4286068e-6;
+        const TANH_ONE: Self = 9.1;
+        const RSQRT_ITERATIONS: [usize; 3] = [1, 2, 3];
+        // kernel polynomials for [Low, Medium, High], lowest degree first
+        const EXP: [&'static [f32]; 3] = [
+            &[1., 1., 0.5037656, 0.16741914],
+            &[1., 0.9999849, 0.4999975, 0.16767032, 0.041833837],
+            &[1., 1., 0.5, 0.16666667, 0.04166635, 0.008333298, 0.0013941119, 0.00019899286],
+        ];
+        const LN: [&'static [f32]; 3] = [
+            &[2., 0.6726173],
+            &[2., 0.666635, 0.40858358],
+            &[2., 0.66666687, 0.39988777, 0.29580054],
+        ];
+        const SIN: [&'static [f32]; 3] = [
+            &[1., -0.16242792],
+            &[1., -0.16665731, 0.008211844],
+            &[1., -0.16666667, 0.008333332, -0.00019840087, 2.7249896e-6],
+        ];
+        const COS: [&'static [f32]; 3] = [
+            &[1., -0.49993464, 0.040818054],
+            &[1., -0.49999982, 0.041661408, -0.0013661145],
+            &[1., -0.5, 0.04166665, -0.0013887589, 2.4463754e-5],
+        ];
+        const ATAN: [&'static [f32]; 3] = [
+            &[1., -0.33287007, 0.17804311],
+            &[1., -0.33331895, 0.1984807, -0.11819232],
+            &[1., -0.3333333, 0.1999954, -0.1426395, 0.10743669, -0.064517185],
+        ];
+        const TANH: [&'static [f32]; 3] = [
+            &[1., -0.33278427, 0.11859109],
+            &[1., -0.33331746, 0.13238269, -0.04526023],
+            &[1., -0.3333333, 0.13333113, -0.053909414, 0.02130929, -0.006610042],
+        ];
+    };
+    (@f64) => {
+        const EXP_MIN: Self = -708.39;
+        const EXP_MAX: Self = 709.78;
+        const LN_2_HI: Self = 0.6931471803691238;
+        const LN_2_LO: Self = 1.9082149292705877e-10;
+        const TANH_ONE: Self = 19.1;
+        const RSQRT_ITERATIONS: [usize; 3] = [2, 3, 4];
+        // kernel polynomials for [Low, Medium, High], lowest degree first
+        const EXP: [&'static [f64]; 3] = [
+            &[
+                1., 1., 0.49999371887106436, 0.16666576989698775, 0.04187568633968518,
+                0.008363179052722578,
+            ],
+            &[
+                1., 0.9999999999955055, 0.4999999999995507, 0.1666666678638044,
+                0.041666666786337556, 0.008333283518768105, 0.001388883909064138,
+                0.00019907582591325134, 2.4867883453521802e-5,
+            ],
+            &[
+                1., 1., 0.5, 0.1666666666666668, 0.04166666666666668, 0.008333333333319589,
+                0.0013888888888879073, 0.00019841269890076403, 2.4801587336442236e-5,
+                2.755724088722987e-6, 2.75572632790885e-7, 2.5110049204818658e-8,
+                2.0918137737642736e-9,
+            ],
+        ];
+        const LN: [&'static [f64]; 3] = [
+            &[2., 0.6666349881407492, 0.4085835694537233],
+            &[
+                2., 0.6666666666737545, 0.39999998796848674, 0.2857175463573276,
+                0.22191394493175057, 0.19362776836747533,
+            ],
+            &[
+                2., 0.666666666666667, 0.39999999999899444, 0.2857142862600327, 0.22222211130259878,
+                0.18182889455674947, 0.15331710618210773, 0.14616585424888623,
+            ],
+        ];
+        const SIN: [&'static [f64]; 3] = [
+            &[1., -0.16666664661714697, 0.008332748154085032, -0.00019587865702101875],
+            &[
+                1., -0.16666666666663885, 0.008333333331078323, -0.00019841266916110632,
+                2.7555990664728974e-6, -2.4805611712122475e-8,
+            ],
+            &[
+                1., -0.16666666666666666, 0.008333333333333331, -0.00019841269841265063,
+                2.7557319219337312e-6, -2.5052106231802837e-8, 1.6058531516797758e-10,
+                -7.586691094197958e-13,
+            ],
+        ];
+        const COS: [&'static [f64]; 3] = [
+            &[1., -0.49999981989148923, 0.04166140952785522, -0.0013661144366576522],
+            &[
+                1., -0.49999999999963873, 0.041666666637384386, -0.0013888885090262895,
+                2.4799861846361453e-5, -2.7237108576245085e-7,
+            ],
+            &[
+                1., -0.5, 0.04166666666666664, -0.001388888888888077, 2.4801587293690305e-5,
+                -2.755731556524682e-7, 2.0875886564482672e-9, -1.1367988423294987e-11,
+            ],
+        ];
+        const ATAN: [&'static [f64]; 3] = [
+            &[
+                1., -0.33333286546448576, 0.19991235338051427, -0.14024096591096666,
+                0.08520277828028164,
+            ],
+            &[
+                1., -0.33333333331439496, 0.19999998916690562, -0.14285612465275252,
+                0.11107493853432703, -0.09028967562110418, 0.07135234647464041,
+                -0.04043033696147107,
+            ],
+            &[
+                1., -0.3333333333333333, 0.19999999999999804, -0.14285714285659779,
+                0.11111111105150588, -0.09090908753259112, 0.07692296368042296, -0.0666642476020052,
+                0.05878927554473807, -0.05230443640686948, 0.045515447340221615,
+                -0.03456931506332609, 0.016284375050043393,
+            ],
+        ];
+        const TANH: [&'static [f64]; 3] = [
+            &[
+                1., -0.3333328746139508, 0.1332846265870873, -0.05314659683261756,
+                0.01729536336436998,
+            ],
+            &[
+                1., -0.33333333332225384, 0.13333332973980913, -0.05396806269763969,
+                0.02186564648352331, -0.008826125562536005, 0.003404727753169859,
+                -0.0009655357337362958,
+            ],
+            &[
+                1., -0.3333333333333333, 0.13333333333332714, -0.05396825396743333,
+                0.021869488493635535, -0.00886323439708993, 0.003592110369136933,
+                -0.001455661753739356, 0.0005889356755666889, -0.0002346336232753106,
+                8.511130450065171e-5, -2.0601492140831683e-5,
+            ],
+        ];
+    };
+}
+
+impl_approx_float! {
+    f32, u32, 12582912., 0x3f3504f3, 0x5f375a86u32, reduce_half_pi_f32;
+    f64, u64, 6755399441055744., 0x3fe6a09e667f3bcd, 0x5fe6eb50c7b537a9u64, reduce_half_pi_f64;
+}
+
+/// For `f32`, the reduction is done in `f64`, with π/2 split into a 28 bit part (so that
+/// `k * part` is exact) and the rest.
+#[inline(always)]
+fn reduce_half_pi_f32(x: f32) -> (i32, f32) {
+    const PIO2_1: f64 = 1.5707963109016418;
+    const PIO2_1T: f64 = 1.5893254773528196e-8;
+    let x = x as f64;
+    let (k, kf) = (x * std::f64::consts::FRAC_2_PI).round_int();
+    (k, ((x - kf * PIO2_1) - kf * PIO2_1T) as f32)
+}
+
+/// For `f64`, π/2 is split into two 33 bit parts and the rest.
+#[inline(always)]
+fn reduce_half_pi_f64(x: f64) -> (i32, f64) {
+    const PIO2_1: f64 = 1.5707963267341256;
+    const PIO2_2: f64 = 6.077100506303966e-11;
+    const PIO2_3: f64 = 2.0222662487959506e-21;
+    let (k, kf) = (x * std::f64::consts::FRAC_2_PI).round_int();
+    (k, ((x - kf * PIO2_1) - kf * PIO2_2) - kf * PIO2_3)
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FF32, FF64};
+
+    /// Order-preserving map of floats to integers
+    fn key(bits: u64, sign: u64) -> i64 {
+        if bits & sign == 0 {
+            bits as i64
+        } else {
+            -((bits & !sign) as i64)
+        }
+    }
+
+    fn unkey(key: i64, sign: u64) -> u64 {
+        if key >= 0 {
+            key as u64
+        } else {
+            (-key) as u64 | sign
+        }
+    }
+
+    /// Error in `f32` ULP of `approx` against `exact`
+    fn ulps_f32(approx: f32, exact: f64) -> f64 {
+        let e = ((exact.to_bits() >> 52) & 0x7ff) as i32 - 1023;
+        (approx as f64 - exact).abs() / 2f64.powi((e - 23).max(-149))
+    }
+
+    /// Error in `f64` ULP of `approx` against `exact`
+    fn ulps_f64(approx: f64, exact: f64) -> f64 {
+        let e = ((exact.to_bits() >> 52) & 0x7ff) as i32 - 1023;
+        let ulp = if e - 52 >= -1022 {
+            2f64.powi(e - 52)
+        } else {
+            f64::from_bits(1 << (e - 52 + 1074).max(0))
+        };
+        (approx - exact).abs() / ulp
+    }
+
+    /// The maximum error of `f` over every `step`-th `f32` in `[lo, hi]`
+    fn max_ulps_f32(
+        (lo, hi): (f32, f32),
+        step: usize,
+        f: impl Fn(FF32) -> FF32,
+        exact: impl Fn(f64) -> f64,
+    ) -> f64 {
+        let sign = 1 << 31;
+        let (lo, hi) = (
+            key(lo.to_bits() as u64, sign),
+            key(hi.to_bits() as u64, sign),
+        );
+        let mut max = 0f64;
+        for k in (lo..=hi).step_by(step) {
+            let x = f32::from_bits(unkey(k, sign) as u32);
+            max = max.max(ulps_f32(*f(FF32::from(x)), exact(x as f64)));
+        }
+        max
+    }
+
+    /// The maximum error of `f` over `n` pseudo-random `f64` in `[lo, hi]`, evenly spread over
+    /// the exponents
+    fn max_ulps_f64(
+        (lo, hi): (f64, f64),
+        n: usize,
+        f: impl Fn(FF64) -> FF64,
+        exact: impl Fn(f64) -> f64,
+    ) -> f64 {
+        let sign = 1 << 63;
+        let (lo, hi) = (key(lo.to_bits(), sign), key(hi.to_bits(), sign));
+        let mut state = 0x2545f4914f6cdd1du64;
+        let mut max = 0f64;
+        for _ in 0..n {
+            let k = lo.wrapping_add((xorshift(&mut state) % hi.wrapping_sub(lo) as u64) as i64);
+            let x = f64::from_bits(unkey(k, sign));
+            max = max.max(ulps_f64(*f(FF64::from(x)), exact(x)));
+        }
+        max
+    }
+
+    const EXP_32: (f32, f32) = (f32::EXP_MIN, f32::EXP_MAX);
+    const EXP_64: (f64, f64) = (f64::EXP_MIN, f64::EXP_MAX);
+    const POSITIVE_32: (f32, f32) = (f32::MIN_POSITIVE, f32::MAX);
+    const POSITIVE_64: (f64, f64) = (f64::MIN_POSITIVE, f64::MAX);
+    const TRIG_32: (f32, f32) = (-1048576., 1048576.);
+    const TRIG_64: (f64, f64) = (-1048576., 1048576.);
+    const ALL_32: (f32, f32) = (f32::MIN, f32::MAX);
+    const ALL_64: (f64, f64) = (f64::MIN, f64::MAX);
+
+    /// Pseudo-random numbers
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    /// Check the documented errors, `[Low, Medium, High]` for `f32` and `f64`
+    macro_rules! check_accuracy {
+        ($step:expr, $samples:expr; $($f:ident: $d32:ident, $d64:ident, $exact:expr,
+            $b32:expr, $b64:expr;)*) => {$(
+            let errors = [
+                max_ulps_f32($d32, $step, |x| Low::$f(x), $exact),
+                max_ulps_f32($d32, $step, |x| Medium::$f(x), $exact),
+                max_ulps_f32($d32, $step, |x| High::$f(x), $exact),
+            ];
+            for (error, bound) in errors.into_iter().zip($b32) {
+                assert!(error <= bound, "{} f32: {} > {}", stringify!($f), error, bound);
+            }
+            let errors = [
+                max_ulps_f64($d64, $samples, |x| Low::$f(x), $exact),
+                max_ulps_f64($d64, $samples, |x| Medium::$f(x), $exact),
+                max_ulps_f64($d64, $samples, |x| High::$f(x), $exact),
+            ];
+            for (error, bound) in errors.into_iter().zip($b64) {
+                assert!(error <= bound, "{} f64: {} > {}", stringify!($f), error, bound);
+            }
+        )*}
+    }
+
+    fn accuracy_with(step: usize, samples: usize) {
+        check_accuracy! {
+            step, samples;
+            exp: EXP_32, EXP_64, f64::exp,
+                [2400., 85., 2.], [1.5e9, 15000., 2.];
+            ln: POSITIVE_32, POSITIVE_64, f64::ln,
+                [1100., 8., 3.], [3.5e9, 700., 3.];
+            log2: POSITIVE_32, POSITIVE_64, f64::log2,
+                [1500., 12., 4.], [5e9, 1000., 3.];
+            sin: TRIG_32, TRIG_64, f64::sin,
+                [9500., 80., 2.], [1.1e9, 2000., 2.];
+            cos: TRIG_32, TRIG_64, f64::cos,
+                [9500., 80., 2.], [1.1e9, 2000., 2.];
+            tan: TRIG_32, TRIG_64, f64::tan,
+                [10500., 115., 4.], [1.5e9, 2700., 4.];
+            atan: ALL_32, ALL_64, f64::atan,
+                [1000., 35., 3.], [6e8, 21000., 3.];
+            tanh: ALL_32, ALL_64, f64::tanh,
+                [2700., 80., 2.], [1.2e9, 28000., 2.];
+            rsqrt: POSITIVE_32, POSITIVE_64, |x: f64| 1. / x.sqrt(),
+                [28400., 75., 3.], [4e10, 250000., 3.];
+        }
+    }
+
+    #[test]
+    fn accuracy() {
+        accuracy_with(10007, 1 << 12);
+    }
+
+    /// Every `f32` in the domains; takes most of an hour in release mode
+    #[test]
+    #[ignore]
+    fn accuracy_exhaustive() {
+        accuracy_with(1, 1 << 24);
+    }
+
+    #[test]
+    fn atan2() {
+        fn check<A: Accuracy>(bound32: f64, bound64: f64) {
+            let mut state = 0x2545f4914f6cdd1d;
+            for _ in 0..1 << 12 {
+                let mut random_f32 = || {
+                    let bits = xorshift(&mut state) as u32;
+                    f32::from_bits(bits & 0x7f7fffff | bits << 31).clamp(-1e15, 1e15)
+                };
+                let (y, x) = (random_f32(), random_f32());
+                let error = ulps_f32(
+                    *A::atan2(FF32::from(y), FF32::from(x)),
+                    (y as f64).atan2(x as f64),
+                );
+                assert!(
+                    error <= bound32,
+                    "atan2({}, {}): {} > {}",
+                    y,
+                    x,
+                    error,
+                    bound32
+                );
+                let mut random_f64 = || {
+                    let bits = xorshift(&mut state);
+                    f64::from_bits(bits & 0x7fefffffffffffff | bits << 63).clamp(-1e100, 1e100)
+                };
+                let (y, x) = (random_f64(), random_f64());
+                let error = ulps_f64(*A::atan2(FF64::from(y), FF64::from(x)), y.atan2(x));
+                assert!(
+                    error <= bound64,
+                    "atan2({}, {}): {} > {}",
+                    y,
+                    x,
+                    error,
+                    bound64
+                );
+            }
+        }
+        check::<Low>(1000., 6e8);
+        check::<Medium>(35., 21000.);
+        check::<High>(4., 3.);
+
+        for (y, x) in [
+            (1., 1.),
+            (1., -1.),
+            (-1., -1.),
+            (-1., 1.),
+            (0., -1.),
+            (1., 0.),
+        ] {
+            let exact = f64::atan2(y, x);
+            assert!(ulps_f64(*High::atan2(FF64::from(y), FF64::from(x)), exact) <= 3.);
+        }
+        assert_eq!(High::atan2(FF64::from(0.), FF64::from(0.)), FF64::from(0.));
+    }
+
+    #[test]
+    fn sincos() {
+        for x in [-10., -1., 0., 0.5, 2., 4., 1e5] {
+            let x = FF64::from(x);
+            assert_eq!(Medium::sincos(x), (Medium::sin(x), Medium::cos(x)));
+        }
+    }
+
+    #[test]
+    fn special_values() {
+        assert_eq!(High::exp(FF64::from(0.)), FF64::from(1.));
+        assert_eq!(High::ln(FF32::from(1.)), FF32::from(0.));
+        assert_eq!(High::log2(FF64::from(8.)), FF64::from(3.));
+        assert_eq!(Low::tanh(FF32::from(-20.)), FF32::from(-1.));
+        // outside of the domain the results are finite
+        assert!(Low::exp(FF32::from(1000.)).is_finite());
+        assert!(Low::exp(FF64::from(-1000.)).is_finite());
+        assert!(High::ln(FF64::from(0.)).is_finite());
+        assert!(High::ln(FF32::from(-1.)).is_finite());
+    }
+}
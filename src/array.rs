@@ -0,0 +1,174 @@
+//! Fixed-size vectors: `Fast<[F; N]>`.
+//!
+//! The operators work element-wise, with the fast-math flags of the wrapper, and broadcast
+//! scalar operands to every element.
+
+use crate::flags::FlagSet;
+use crate::{Fast, FastFloat};
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+macro_rules! impl_array_op {
+    ($($name:ident, $method:ident;)*) => {
+        $(
+        // Fast<[F; N]> + Fast<[F; N]>
+        impl<F: FastFloat, const N: usize, Flags: FlagSet> $name for Fast<[F; N], Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(mut self, rhs: Self) -> Self::Output {
+                for (x, y) in self.0.iter_mut().zip(rhs.0) {
+                    *x = *Fast::<F, Flags>(*x, PhantomData).$method(y);
+                }
+                self
+            }
+        }
+
+        // Fast<[F; N]> + Fast<F>
+        impl<F: FastFloat, const N: usize, Flags: FlagSet> $name<Fast<F, Flags>>
+            for Fast<[F; N], Flags>
+        {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(mut self, rhs: Fast<F, Flags>) -> Self::Output {
+                for x in &mut self.0 {
+                    *x = *Fast::<F, Flags>(*x, PhantomData).$method(rhs);
+                }
+                self
+            }
+        }
+
+        // Fast<[F; N]> + F
+        impl<F: FastFloat, const N: usize, Flags: FlagSet> $name<F> for Fast<[F; N], Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: F) -> Self::Output {
+                self.$method(Fast::<F, Flags>(rhs, PhantomData))
+            }
+        }
+
+        // Fast<F> + Fast<[F; N]>
+        impl<F: FastFloat, const N: usize, Flags: FlagSet> $name<Fast<[F; N], Flags>>
+            for Fast<F, Flags>
+        {
+            type Output = Fast<[F; N], Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, mut rhs: Fast<[F; N], Flags>) -> Self::Output {
+                for x in &mut rhs.0 {
+                    *x = *self.$method(*x);
+                }
+                rhs
+            }
+        }
+
+        // F + Fast<[F; N]>
+        impl_array_op!(@rev $name, $method; f32 f64);
+        #[cfg(all(nightly, feature = "f16"))]
+        impl_array_op!(@rev $name, $method; f16);
+        #[cfg(all(nightly, feature = "f128"))]
+        impl_array_op!(@rev $name, $method; f128);
+        )*
+    };
+    (@rev $name:ident, $method:ident; $($f:ty)*) => {
+        $(
+        impl<const N: usize, Flags: FlagSet> $name<Fast<[$f; N], Flags>> for $f {
+            type Output = Fast<[$f; N], Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: Fast<[$f; N], Flags>) -> Self::Output {
+                Fast::<$f, Flags>(self, PhantomData).$method(rhs)
+            }
+        }
+        )*
+    };
+}
+
+impl_array_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+}
+
+/// Vector operations
+impl<F: FastFloat, const N: usize, Flags: FlagSet> Fast<[F; N], Flags> {
+    /// Apply `f` to every element
+    #[inline(always)]
+    pub fn map<G>(
+        self,
+        mut f: impl FnMut(Fast<F, Flags>) -> Fast<G, Flags>,
+    ) -> Fast<[G; N], Flags> {
+        Fast(self.0.map(|x| f(Fast(x, PhantomData)).0), PhantomData)
+    }
+
+    /// The sum of the elements
+    #[inline(always)]
+    pub fn sum(self) -> Fast<F, Flags> {
+        self.0.iter().sum()
+    }
+
+    /// The dot product with `other`
+    #[inline(always)]
+    pub fn dot(self, other: Self) -> Fast<F, Flags> {
+        (self * other).sum()
+    }
+
+    /// The euclidean norm
+    ///
+    /// This is nightly only, since it needs the `sqrt` intrinsic.
+    #[cfg(nightly)]
+    #[inline(always)]
+    pub fn norm(self) -> Fast<F, Flags> {
+        self.dot(self).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::flags::AllFast;
+    use crate::{Fast, FF32, FF64};
+
+    fn fast<const N: usize>(xs: [f64; N]) -> Fast<[f64; N], AllFast> {
+        Fast::from(xs)
+    }
+
+    macro_rules! test_op {
+        ($($op:tt)+) => {
+            $(
+                let (x, y) = ([4., 6., 8.], [2., 3., 4.]);
+                let z = [x[0] $op y[0], x[1] $op y[1], x[2] $op y[2]];
+                assert_eq!(fast(x) $op fast(y), fast(z));
+                let z = [x[0] $op 2., x[1] $op 2., x[2] $op 2.];
+                assert_eq!(fast(x) $op 2., fast(z));
+                assert_eq!(fast(x) $op FF64::from(2.), fast(z));
+                let z = [2. $op x[0], 2. $op x[1], 2. $op x[2]];
+                assert_eq!(2. $op fast(x), fast(z));
+                assert_eq!(FF64::from(2.) $op fast(x), fast(z));
+            )+
+        }
+    }
+
+    #[test]
+    fn each_op() {
+        test_op!(+ - * / %);
+
+        let mut x = fast([1., 2.]);
+        x += fast([1., 1.]);
+        x *= 2.;
+        assert_eq!(x, fast([4., 6.]));
+    }
+
+    #[test]
+    fn vector_ops() {
+        let x = Fast::<[f32; 3]>::from([1., 2., 2.]);
+        assert_eq!(x.sum(), FF32::from(5.));
+        assert_eq!(x.dot(x), FF32::from(9.));
+        assert_eq!(x.map(|x| x * x), Fast::from([1., 4., 4.]));
+        #[cfg(nightly)]
+        assert_eq!(x.norm(), FF32::from(3.));
+    }
+}
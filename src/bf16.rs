@@ -0,0 +1,331 @@
+//! `BF16`, a bfloat16 storage type.
+//!
+//! bfloat16 is the upper half of an `f32`: the same exponent range, with 8 bits of
+//! precision. It is meant for storage; `Fast<BF16, Flags>` computes by widening to
+//! `Fast<f32, Flags>`, using its operators, and rounding the result back.
+
+use crate::flags::FlagSet;
+use crate::Fast;
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// bfloat16: a 16 bit float with the exponent range of `f32`.
+///
+/// Converting to `f32` is exact, converting from `f32` rounds to nearest, ties to even. NaN
+/// stays NaN, signaling NaNs become quiet. Comparisons and formatting go through `f32`.
+#[derive(Copy, Clone, Default)]
+#[repr(transparent)]
+pub struct BF16(u16);
+
+impl BF16 {
+    /// Zero
+    pub const ZERO: Self = BF16(0);
+    /// One
+    pub const ONE: Self = BF16(0x3f80);
+    /// Machine epsilon, `2^-7`
+    pub const EPSILON: Self = BF16(0x3c00);
+    /// Smallest positive normal value, the same as for `f32`
+    pub const MIN_POSITIVE: Self = BF16(0x0080);
+    /// Largest finite value
+    pub const MAX: Self = BF16(0x7f7f);
+    /// Smallest finite value
+    pub const MIN: Self = BF16(0xff7f);
+    /// Infinity
+    pub const INFINITY: Self = BF16(0x7f80);
+    /// Negative infinity
+    pub const NEG_INFINITY: Self = BF16(0xff80);
+    /// Not a number
+    pub const NAN: Self = BF16(0x7fc0);
+
+    /// The value with the bit pattern `bits`
+    #[inline(always)]
+    pub const fn from_bits(bits: u16) -> Self {
+        BF16(bits)
+    }
+
+    /// The bit pattern of the value
+    #[inline(always)]
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// Round `x` to the nearest `BF16`, ties to even
+    ///
+    /// Values beyond the range of `BF16` round to infinity.
+    #[inline(always)]
+    pub const fn from_f32(x: f32) -> Self {
+        let bits = x.to_bits();
+        // Adding just under half an ulp, plus one for odd values, carries into the upper half
+        // exactly when rounding up. A carry into the exponent is still correct.
+        let rounded = bits.wrapping_add(0x7fff + ((bits >> 16) & 1)) >> 16;
+        let quiet_nan = (bits >> 16) | 0x0040;
+        BF16(if x.is_nan() { quiet_nan } else { rounded } as u16)
+    }
+
+    /// The value as `f32`, exactly
+    #[inline(always)]
+    pub const fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+
+    /// Whether the value is NaN
+    #[inline(always)]
+    pub const fn is_nan(self) -> bool {
+        (self.0 & 0x7fff) > 0x7f80
+    }
+
+    /// Widen every element of `src` into `dst`
+    ///
+    /// # Panics
+    ///
+    /// If the slices have different lengths.
+    pub fn widen_slice(src: &[BF16], dst: &mut [f32]) {
+        assert_eq!(src.len(), dst.len(), "slices of different length");
+        for (y, &x) in dst.iter_mut().zip(src) {
+            *y = x.to_f32();
+        }
+    }
+
+    /// Round every element of `src` into `dst`, see [`BF16::from_f32`]
+    ///
+    /// # Panics
+    ///
+    /// If the slices have different lengths.
+    pub fn narrow_slice(src: &[f32], dst: &mut [BF16]) {
+        assert_eq!(src.len(), dst.len(), "slices of different length");
+        for (y, &x) in dst.iter_mut().zip(src) {
+            *y = BF16::from_f32(x);
+        }
+    }
+}
+
+impl From<BF16> for f32 {
+    #[inline(always)]
+    fn from(x: BF16) -> Self {
+        x.to_f32()
+    }
+}
+
+impl PartialEq for BF16 {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.to_f32() == other.to_f32()
+    }
+}
+
+impl PartialOrd for BF16 {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.to_f32().partial_cmp(&other.to_f32())
+    }
+}
+
+impl Neg for BF16 {
+    type Output = Self;
+    #[inline(always)]
+    fn neg(self) -> Self::Output {
+        BF16(self.0 ^ 0x8000)
+    }
+}
+
+macro_rules! impl_format {
+    ($($name:ident)+) => {
+        $(
+        impl fmt::$name for BF16 {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::$name::fmt(&self.to_f32(), f)
+            }
+        }
+        )+
+    }
+}
+
+impl_format!(Debug Display LowerExp UpperExp);
+
+/// Exact
+impl<Flags> From<Fast<BF16, Flags>> for Fast<f32, Flags> {
+    #[inline(always)]
+    fn from(x: Fast<BF16, Flags>) -> Self {
+        Fast(x.0.to_f32(), PhantomData)
+    }
+}
+
+impl<Flags: FlagSet> Fast<f32, Flags> {
+    /// Round to the nearest `BF16`, ties to even
+    ///
+    /// Values beyond the range of `BF16` round to infinity, which is invalid for
+    /// [`AllFast`](crate::flags::AllFast); the `sanitize` feature catches that.
+    #[inline(always)]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    pub fn to_bf16(self) -> Fast<BF16, Flags> {
+        let result = BF16::from_f32(self.0);
+        #[cfg(feature = "sanitize")]
+        crate::sanitize::<_, Flags>("to_bf16", "result", result.to_f32());
+        Fast(result, PhantomData)
+    }
+}
+
+macro_rules! impl_op {
+    ($($name:ident, $method:ident;)*) => {
+        $(
+        // Fast<BF16> + BF16
+        impl<Flags: FlagSet> $name<BF16> for Fast<BF16, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: BF16) -> Self::Output {
+                Fast::<f32, Flags>::from(self).$method(rhs.to_f32()).to_bf16()
+            }
+        }
+
+        // Fast<BF16> + Fast<BF16>
+        impl<Flags: FlagSet> $name for Fast<BF16, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: Self) -> Self::Output {
+                self.$method(rhs.0)
+            }
+        }
+        )*
+    };
+}
+
+impl_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+}
+
+impl<Flags> Neg for Fast<BF16, Flags> {
+    type Output = Self;
+    #[inline(always)]
+    fn neg(self) -> Self::Output {
+        Fast(-self.0, PhantomData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BF16;
+    use crate::flags::Strict;
+    use crate::{Fast, FF32};
+
+    fn all() -> impl Iterator<Item = BF16> {
+        (0..=u16::MAX).map(BF16::from_bits)
+    }
+
+    #[test]
+    fn round_trip() {
+        for x in all() {
+            let y = BF16::from_f32(x.to_f32());
+            if x.is_nan() {
+                // quiet NaNs are unchanged, signaling NaNs become quiet
+                assert_eq!(y.to_bits(), x.to_bits() | 0x0040);
+            } else {
+                assert_eq!(y.to_bits(), x.to_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn round_to_nearest_even() {
+        for x in all().filter(|x| x.to_f32().is_finite()) {
+            let bits = (x.to_bits() as u32) << 16;
+            // the next value away from zero
+            let next = x.to_bits() + 1;
+            let even = if x.to_bits() % 2 == 0 {
+                x.to_bits()
+            } else {
+                next
+            };
+            assert_eq!(
+                BF16::from_f32(f32::from_bits(bits | 0x0001)).to_bits(),
+                x.to_bits()
+            );
+            assert_eq!(
+                BF16::from_f32(f32::from_bits(bits | 0x7fff)).to_bits(),
+                x.to_bits()
+            );
+            assert_eq!(
+                BF16::from_f32(f32::from_bits(bits | 0x8000)).to_bits(),
+                even
+            );
+            assert_eq!(
+                BF16::from_f32(f32::from_bits(bits | 0x8001)).to_bits(),
+                next
+            );
+            assert_eq!(
+                BF16::from_f32(f32::from_bits(bits | 0xffff)).to_bits(),
+                next
+            );
+        }
+        assert_eq!(BF16::from_f32(f32::MAX), BF16::INFINITY);
+        assert_eq!(BF16::from_f32(-f32::MAX), BF16::NEG_INFINITY);
+        assert_eq!(BF16::from_f32(1e-45).to_bits(), 0);
+        assert_eq!(BF16::from_f32(-0.).to_bits(), 0x8000);
+        assert_eq!(BF16::from_f32(1. / 3.).to_f32(), 171. / 512.);
+    }
+
+    #[test]
+    fn slices() {
+        let xs: [BF16; 0x10000] = core::array::from_fn(|i| BF16::from_bits(i as u16));
+        let mut wide = [0f32; 0x10000];
+        BF16::widen_slice(&xs, &mut wide);
+        let mut narrow = [BF16::ZERO; 0x10000];
+        BF16::narrow_slice(&wide, &mut narrow);
+        for ((x, y), z) in xs.iter().zip(&narrow).zip(&wide) {
+            assert_eq!(z.to_bits(), x.to_f32().to_bits());
+            if !x.is_nan() {
+                assert_eq!(y.to_bits(), x.to_bits());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "slices of different length")]
+    fn slices_length() {
+        BF16::widen_slice(&[BF16::ONE; 3], &mut [0.; 2]);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let (x, y) = (
+            Fast::<BF16>::from(BF16::ONE),
+            Fast::from(BF16::from_f32(0.25)),
+        );
+        assert_eq!(*Fast::<f32>::from(x + y), 1.25);
+        assert_eq!(*Fast::<f32>::from(x - y), 0.75);
+        assert_eq!(*Fast::<f32>::from(x * y), 0.25);
+        assert_eq!(*Fast::<f32>::from(x / y), 4.);
+        assert_eq!(*Fast::<f32>::from(y % x), 0.25);
+        assert_eq!(*Fast::<f32>::from(-x), -1.);
+        let mut z = x;
+        z += BF16::EPSILON;
+        z *= y;
+        assert_eq!(*z, BF16::from_f32(129. / 512.));
+
+        // 1 + 2^-8 is halfway between 1 and 1 + 2^-7, rounds to even
+        let half_epsilon = BF16::from_f32(2f32.powi(-8));
+        assert_eq!(*(x + half_epsilon), BF16::ONE);
+        assert_eq!(
+            *(x + BF16::EPSILON + half_epsilon),
+            BF16::from_f32(1. + 2. / 128.)
+        );
+
+        let big = Fast::<BF16, Strict>::from(BF16::MAX);
+        assert_eq!(*(big * BF16::from_f32(2.)), BF16::INFINITY);
+        assert_eq!(FF32::from(3.).to_bf16(), Fast::from(BF16::from_f32(3.)));
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "result of `to_bf16` is invalid: value is infinite")]
+    fn sanitize_overflow() {
+        let _ = FF32::MAX.to_bf16();
+    }
+}
@@ -0,0 +1,547 @@
+//! Complex numbers: [`FastComplex`].
+//!
+//! The real and imaginary parts are `Fast` values, so every operation uses the fast-math
+//! flags of the parts. Multiplication and division use the textbook formulas, without the
+//! scaling that guards against overflow and without special cases for infinite values.
+//!
+//! The slice functions [`dot`] and [`dotc`] use several accumulators, like the functions in
+//! [`reduce`](crate::reduce).
+
+#[cfg(nightly)]
+use crate::approx::High;
+use crate::approx::{Accuracy, ApproxFloat};
+use crate::flags::{AllFast, FlagSet};
+use crate::reduce::{pairwise, ACCUMULATORS};
+use crate::{Fast, FastFloat};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A complex number `re + im * i` with fast-math parts.
+#[repr(C)]
+pub struct FastComplex<F, Flags = AllFast> {
+    /// The real part
+    pub re: Fast<F, Flags>,
+    /// The imaginary part
+    pub im: Fast<F, Flags>,
+}
+
+impl<F: Copy, Flags> Copy for FastComplex<F, Flags> {}
+
+impl<F: Clone, Flags> Clone for FastComplex<F, Flags> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        FastComplex {
+            re: self.re.clone(),
+            im: self.im.clone(),
+        }
+    }
+}
+
+impl<F: PartialEq, Flags> PartialEq for FastComplex<F, Flags> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.re == other.re && self.im == other.im
+    }
+}
+
+impl<F: fmt::Debug, Flags> fmt::Debug for FastComplex<F, Flags> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FastComplex")
+            .field("re", &self.re)
+            .field("im", &self.im)
+            .finish()
+    }
+}
+
+impl<F, Flags> FastComplex<F, Flags> {
+    /// The complex number `re + im * i`
+    #[inline(always)]
+    pub const fn new(re: Fast<F, Flags>, im: Fast<F, Flags>) -> Self {
+        FastComplex { re, im }
+    }
+}
+
+/// Constants
+impl<F: FastFloat, Flags> FastComplex<F, Flags> {
+    /// Zero
+    pub const ZERO: Self = FastComplex::new(Fast::ZERO, Fast::ZERO);
+    /// One
+    pub const ONE: Self = FastComplex::new(Fast::ONE, Fast::ZERO);
+    /// The imaginary unit
+    pub const I: Self = FastComplex::new(Fast::ZERO, Fast::ONE);
+}
+
+impl<F: FastFloat, Flags: FlagSet> FastComplex<F, Flags> {
+    /// The complex conjugate, `re - im * i`
+    #[inline(always)]
+    pub fn conj(self) -> Self {
+        FastComplex::new(self.re, -self.im)
+    }
+
+    /// The squared absolute value, `re² + im²`
+    #[inline(always)]
+    pub fn norm_sqr(self) -> Fast<F, Flags> {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+/// Polar form
+///
+/// These use the math functions of `Fast`, so they are nightly only. There is no `atan2`
+/// intrinsic, so [`FastComplex::arg`] uses the [`High`] approximation, within a few ULP.
+#[cfg(nightly)]
+impl<F: ApproxFloat, Flags: FlagSet> FastComplex<F, Flags> {
+    /// The absolute value, `sqrt(re² + im²)`
+    ///
+    /// This overflows when `norm_sqr` does, and underflows with it too: `norm_sqr` is
+    /// subnormal or zero for parts below about `1e-154` (`f64`), so `1e-170` gives `0`.
+    #[inline]
+    pub fn abs(self) -> Fast<F, Flags> {
+        self.norm_sqr().sqrt()
+    }
+
+    /// The argument, in radians in `[-π, π]`, see [`Accuracy::atan2`]
+    #[inline]
+    pub fn arg(self) -> Fast<F, Flags> {
+        High::atan2(self.im, self.re)
+    }
+
+    /// `e^self`
+    #[inline]
+    pub fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    /// The complex number with absolute value `r` and argument `theta`, in radians
+    #[inline]
+    pub fn from_polar(r: Fast<F, Flags>, theta: Fast<F, Flags>) -> Self {
+        FastComplex::new(r * theta.cos(), r * theta.sin())
+    }
+}
+
+/// Approximate polar form
+///
+/// These use the approximations of [`approx`](crate::approx) at the accuracy tier `A`, so
+/// they work on stable.
+impl<F: ApproxFloat, Flags: FlagSet> FastComplex<F, Flags> {
+    /// The absolute value, `sqrt(re² + im²)`
+    ///
+    /// This is `norm_sqr() * A::rsqrt(norm_sqr())`, so it overflows when `norm_sqr` does.
+    /// It also underflows with it: a subnormal `norm_sqr` is outside the domain of `rsqrt`,
+    /// so the result is unspecified, and `1e-170` gives `0`.
+    #[inline]
+    pub fn abs_approx<A: Accuracy>(self) -> Fast<F, Flags> {
+        let n = self.norm_sqr();
+        if n.0 == F::ZERO {
+            n
+        } else {
+            n * A::rsqrt(n)
+        }
+    }
+
+    /// The argument, in radians in `[-π, π]`, see [`Accuracy::atan2`]
+    #[inline]
+    pub fn arg_approx<A: Accuracy>(self) -> Fast<F, Flags> {
+        A::atan2(self.im, self.re)
+    }
+
+    /// `e^self`
+    #[inline]
+    pub fn exp_approx<A: Accuracy>(self) -> Self {
+        Self::from_polar_approx::<A>(A::exp(self.re), self.im)
+    }
+
+    /// The complex number with absolute value `r` and argument `theta`, in radians
+    #[inline]
+    pub fn from_polar_approx<A: Accuracy>(r: Fast<F, Flags>, theta: Fast<F, Flags>) -> Self {
+        let (sin, cos) = A::sincos(theta);
+        FastComplex::new(r * cos, r * sin)
+    }
+}
+
+macro_rules! impl_complex {
+    ([$($c:tt)?]) => {
+        /// The real number `x`
+        impl<F: FastFloat, Flags> $($c)? From<Fast<F, Flags>> for FastComplex<F, Flags> {
+            #[inline(always)]
+            fn from(x: Fast<F, Flags>) -> Self {
+                FastComplex::new(x, Fast::ZERO)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags> $($c)? Neg for FastComplex<F, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            fn neg(self) -> Self::Output {
+                FastComplex::new(-self.re, -self.im)
+            }
+        }
+
+        // FastComplex<F> + FastComplex<F>
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Add for FastComplex<F, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn add(self, rhs: Self) -> Self::Output {
+                FastComplex::new(self.re + rhs.re, self.im + rhs.im)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Sub for FastComplex<F, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn sub(self, rhs: Self) -> Self::Output {
+                FastComplex::new(self.re - rhs.re, self.im - rhs.im)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Mul for FastComplex<F, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn mul(self, rhs: Self) -> Self::Output {
+                FastComplex::new(
+                    self.re * rhs.re - self.im * rhs.im,
+                    self.re * rhs.im + self.im * rhs.re,
+                )
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Div for FastComplex<F, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn div(self, rhs: Self) -> Self::Output {
+                let d = rhs.re * rhs.re + rhs.im * rhs.im;
+                FastComplex::new(
+                    (self.re * rhs.re + self.im * rhs.im) / d,
+                    (self.im * rhs.re - self.re * rhs.im) / d,
+                )
+            }
+        }
+
+        // FastComplex<F> + Fast<F>
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Add<Fast<F, Flags>>
+            for FastComplex<F, Flags>
+        {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn add(self, rhs: Fast<F, Flags>) -> Self::Output {
+                FastComplex::new(self.re + rhs, self.im)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Sub<Fast<F, Flags>>
+            for FastComplex<F, Flags>
+        {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn sub(self, rhs: Fast<F, Flags>) -> Self::Output {
+                FastComplex::new(self.re - rhs, self.im)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Mul<Fast<F, Flags>>
+            for FastComplex<F, Flags>
+        {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn mul(self, rhs: Fast<F, Flags>) -> Self::Output {
+                FastComplex::new(self.re * rhs, self.im * rhs)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Div<Fast<F, Flags>>
+            for FastComplex<F, Flags>
+        {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn div(self, rhs: Fast<F, Flags>) -> Self::Output {
+                FastComplex::new(self.re / rhs, self.im / rhs)
+            }
+        }
+
+        // Fast<F> + FastComplex<F>
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Add<FastComplex<F, Flags>>
+            for Fast<F, Flags>
+        {
+            type Output = FastComplex<F, Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn add(self, rhs: FastComplex<F, Flags>) -> Self::Output {
+                FastComplex::new(self + rhs.re, rhs.im)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Sub<FastComplex<F, Flags>>
+            for Fast<F, Flags>
+        {
+            type Output = FastComplex<F, Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn sub(self, rhs: FastComplex<F, Flags>) -> Self::Output {
+                FastComplex::new(self - rhs.re, -rhs.im)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Mul<FastComplex<F, Flags>>
+            for Fast<F, Flags>
+        {
+            type Output = FastComplex<F, Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn mul(self, rhs: FastComplex<F, Flags>) -> Self::Output {
+                FastComplex::new(self * rhs.re, self * rhs.im)
+            }
+        }
+
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? Div<FastComplex<F, Flags>>
+            for Fast<F, Flags>
+        {
+            type Output = FastComplex<F, Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn div(self, rhs: FastComplex<F, Flags>) -> Self::Output {
+                let d = rhs.re * rhs.re + rhs.im * rhs.im;
+                FastComplex::new(self * rhs.re / d, -(self * rhs.im) / d)
+            }
+        }
+    };
+}
+
+with_constness!(impl_complex! {});
+
+macro_rules! impl_op {
+    ([$($c:tt)?]) => {};
+    ([$($c:tt)?] $name:ident, $method:ident; $($rest:tt)*) => {
+        // FastComplex<F> + F
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? $name<F> for FastComplex<F, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: F) -> Self::Output {
+                self.$method(Fast::<F, Flags>(rhs, PhantomData))
+            }
+        }
+
+        // F + FastComplex<F>, and the same with references to either operand
+        impl_rev_op!([$($c)?] $name, $method; FastComplex);
+        impl_ref_op!([$($c)?] $name, $method; [F: $([$c])? FastFloat, Flags: $([$c])? FlagSet] FastComplex<F, Flags>, FastComplex<F, Flags>);
+        impl_ref_op!([$($c)?] $name, $method; [F: $([$c])? FastFloat, Flags: $([$c])? FlagSet] FastComplex<F, Flags>, Fast<F, Flags>);
+        impl_ref_op!([$($c)?] $name, $method; [F: $([$c])? FastFloat, Flags: $([$c])? FlagSet] FastComplex<F, Flags>, F);
+        impl_ref_op!([$($c)?] $name, $method; [F: $([$c])? FastFloat, Flags: $([$c])? FlagSet] Fast<F, Flags>, FastComplex<F, Flags>);
+
+        impl_op!([$($c)?] $($rest)*);
+    };
+}
+
+with_constness!(impl_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+});
+
+with_constness!(impl_assignop! {
+    FastComplex;
+    AddAssign, add_assign, Add, add;
+    SubAssign, sub_assign, Sub, sub;
+    MulAssign, mul_assign, Mul, mul;
+    DivAssign, div_assign, Div, div;
+});
+
+/// Multiply `xs` and `ys` element-wise, after `f`, and sum the products.
+#[inline(always)]
+fn dot_with<F: FastFloat, Flags: FlagSet>(
+    xs: &[FastComplex<F, Flags>],
+    ys: &[FastComplex<F, Flags>],
+    f: impl Fn(FastComplex<F, Flags>) -> FastComplex<F, Flags>,
+) -> FastComplex<F, Flags> {
+    let n = xs.len().min(ys.len());
+    let (xs, ys) = (&xs[..n], &ys[..n]);
+    let mut acc = [FastComplex::ZERO; ACCUMULATORS];
+    let mut x_chunks = xs.chunks_exact(ACCUMULATORS);
+    let mut y_chunks = ys.chunks_exact(ACCUMULATORS);
+    for (xc, yc) in (&mut x_chunks).zip(&mut y_chunks) {
+        for ((a, &x), &y) in acc.iter_mut().zip(xc).zip(yc) {
+            *a += f(x) * y;
+        }
+    }
+    let rest = x_chunks.remainder().iter().zip(y_chunks.remainder());
+    for (a, (&x, &y)) in acc.iter_mut().zip(rest) {
+        *a += f(x) * y;
+    }
+    pairwise(acc, |a, b| a + b)
+}
+
+/// The dot product of `xs` and `ys`, `Σ x * y`
+///
+/// If the slices have different lengths, the longer one is truncated (like `zip`).
+pub fn dot<F: FastFloat, Flags: FlagSet>(
+    xs: &[FastComplex<F, Flags>],
+    ys: &[FastComplex<F, Flags>],
+) -> FastComplex<F, Flags> {
+    dot_with(xs, ys, |x| x)
+}
+
+/// The dot product of `xs` conjugated and `ys`, `Σ conj(x) * y`
+///
+/// This is the inner product of complex vectors. If the slices have different lengths, the
+/// longer one is truncated (like `zip`).
+pub fn dotc<F: FastFloat, Flags: FlagSet>(
+    xs: &[FastComplex<F, Flags>],
+    ys: &[FastComplex<F, Flags>],
+) -> FastComplex<F, Flags> {
+    dot_with(xs, ys, FastComplex::conj)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{High, Low, Medium};
+    use crate::flags::Strict;
+    use crate::FF64;
+
+    type C64 = FastComplex<f64>;
+
+    fn c(re: f64, im: f64) -> C64 {
+        FastComplex::new(FF64::from(re), FF64::from(im))
+    }
+
+    fn close(a: C64, b: C64, tolerance: f64) -> bool {
+        (*a.re - *b.re).abs() <= tolerance && (*a.im - *b.im).abs() <= tolerance
+    }
+
+    #[test]
+    fn ops() {
+        let (z, w) = (c(1., 2.), c(1., -1.));
+        assert_eq!(z + w, c(2., 1.));
+        assert_eq!(z - w, c(0., 3.));
+        assert_eq!(z * w, c(3., 1.));
+        assert_eq!(z / w, c(-0.5, 1.5));
+        assert_eq!(-z, c(-1., -2.));
+        assert_eq!(C64::I * C64::I, -C64::ONE);
+        assert_eq!(w / w, C64::ONE);
+
+        let mut x = z;
+        x += w;
+        x *= C64::I;
+        x -= 1.;
+        x /= FF64::from(2.);
+        assert_eq!(x, c(-1., 1.));
+    }
+
+    #[test]
+    fn scalar_ops() {
+        let (z, x) = (c(2., -2.), 2.);
+        for y in [z + x, z + FF64::from(x), x + z, FF64::from(x) + z] {
+            assert_eq!(y, c(4., -2.));
+        }
+        assert_eq!(z - x, c(0., -2.));
+        assert_eq!(x - z, c(0., 2.));
+        assert_eq!(z * x, c(4., -4.));
+        assert_eq!(x * z, c(4., -4.));
+        assert_eq!(z / x, c(1., -1.));
+        assert_eq!(x / z, c(0.5, 0.5));
+        assert_eq!(C64::from(FF64::from(x)), c(2., 0.));
+    }
+
+    #[test]
+    #[allow(clippy::op_ref)] // the reference impls are under test
+    fn refs() {
+        let (z, w, x) = (c(1., 2.), c(1., -1.), FF64::from(2.));
+        assert_eq!(&z + w, z + w);
+        assert_eq!(z * &w, z * w);
+        assert_eq!(&z / &w, z / w);
+        assert_eq!(&z - &x, z - x);
+        assert_eq!(&x / &z, x / z);
+        assert_eq!(2. * &z, 2. * z);
+        assert_eq!(&z + &2., z + 2.);
+    }
+
+    #[test]
+    fn conj_norm() {
+        let z = c(3., -4.);
+        assert_eq!(z.conj(), c(3., 4.));
+        assert_eq!(*z.norm_sqr(), 25.);
+        assert_eq!(z * z.conj(), c(25., 0.));
+        assert_eq!(*C64::ZERO.abs_approx::<Low>(), 0.);
+        assert!((*z.abs_approx::<Low>() - 5.).abs() < 1e-4);
+        assert!((*z.abs_approx::<High>() - 5.).abs() < 1e-14);
+    }
+
+    #[test]
+    fn polar() {
+        use std::f64::consts::{FRAC_PI_2, PI};
+        assert_eq!(*C64::ZERO.arg_approx::<High>(), 0.);
+        assert!((*c(0., 1.).arg_approx::<High>() - FRAC_PI_2).abs() < 1e-15);
+        assert!((*c(-1., 0.).arg_approx::<High>() - PI).abs() < 1e-15);
+        assert!((*c(-1., -1.).arg_approx::<Medium>() + 0.75 * PI).abs() < 1e-9);
+
+        // e^(iπ) + 1 = 0
+        let z = c(0., PI).exp_approx::<High>() + 1.;
+        assert!(close(z, C64::ZERO, 1e-15));
+        let z = c(1., 0.5).exp_approx::<High>();
+        let (re, im) = (1f64.exp() * 0.5f64.cos(), 1f64.exp() * 0.5f64.sin());
+        assert!(close(z, c(re, im), 1e-14));
+
+        for z in [c(1., 2.), c(-3., 0.5), c(-0.25, -8.)] {
+            let w = C64::from_polar_approx::<High>(z.abs_approx::<High>(), z.arg_approx::<High>());
+            assert!(close(z, w, 1e-14), "{:?} {:?}", z, w);
+        }
+    }
+
+    #[test]
+    #[cfg(nightly)]
+    fn polar_exact() {
+        use std::f64::consts::PI;
+        assert_eq!(*c(3., -4.).abs(), 5.);
+        assert_eq!(*C64::ZERO.abs(), 0.);
+        assert_eq!(*c(-1., 0.).arg(), PI);
+        let z = c(1., 0.5).exp();
+        assert_eq!(z, c(1f64.exp() * 0.5f64.cos(), 1f64.exp() * 0.5f64.sin()));
+        for z in [c(1., 2.), c(-3., 0.5), c(-0.25, -8.)] {
+            let w = C64::from_polar(z.abs(), z.arg());
+            assert!(close(z, w, 1e-14), "{:?} {:?}", z, w);
+        }
+    }
+
+    #[test]
+    fn dot_products() {
+        let xs: [C64; 37] = core::array::from_fn(|i| c(i as f64, 1. - i as f64));
+        let ys: [C64; 37] = core::array::from_fn(|i| c(0.5 * i as f64, 2.));
+        let regular = |f: fn(C64) -> C64| {
+            xs.iter()
+                .zip(&ys)
+                .fold(C64::ZERO, |acc, (&x, &y)| acc + f(x) * y)
+        };
+        assert_eq!(dot(&xs, &ys), regular(|x| x));
+        assert_eq!(dotc(&xs, &ys), regular(C64::conj));
+        assert_eq!(dotc(&xs, &xs).re, xs.iter().map(|x| x.norm_sqr()).sum());
+        assert_eq!(*dotc(&xs, &xs).im, 0.);
+        assert_eq!(dot(&xs[..3], &ys), dot(&xs[..3], &ys[..3]));
+        assert_eq!(dot::<f64, AllFast>(&[], &[]), C64::ZERO);
+
+        let zs = [FastComplex::<f32, Strict>::I; 5];
+        assert_eq!(*dot(&zs, &zs).re, -5.);
+    }
+
+    #[test]
+    #[cfg(nightly)]
+    fn const_ops() {
+        const Z: FastComplex<f64, Strict> = {
+            let z = FastComplex::new(Fast::ONE, Fast::ONE);
+            let mut w = z * z / (z - Fast::ONE);
+            w += 2.;
+            -w
+        };
+        assert_eq!(*Z.re, -4.);
+        assert_eq!(*Z.im, 0.);
+    }
+}
@@ -0,0 +1,520 @@
+//! Conversions between the float types, and to and from integers.
+//!
+//! Widening is exact, so it is offered as `From`, and the mixed-precision operators widen
+//! the narrower operand, so `f32` products can be accumulated in an `f64` sum with
+//! `acc += x * y`. Narrowing rounds, see [`Fast::to_ff32`]. With the `f16` and `f128`
+//! features, the same conversions exist between all four types.
+//!
+//! Integers convert with `From` where that is exact, like for the primitive floats, and with
+//! rounding methods such as [`Fast::from_i32`] where it is not. The other direction skips the
+//! saturating checks of `as`, see [`Fast::to_int_unchecked`].
+
+use crate::flags::FlagSet;
+use crate::{Fast, FastFloat};
+use std::any::type_name;
+#[cfg(nightly)]
+use std::intrinsics::float_to_int_unchecked;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+// Exact conversions from `$narrow` to `$wide`, and the mixed-precision operators
+macro_rules! impl_widen {
+    ([$($c:tt)?]) => {};
+    ([$($c:tt)?] $narrow:ident => $wide:ident; $($rest:tt)*) => {
+        /// Exact, since every value of the narrower type is representable
+        impl<Flags> $($c)? From<Fast<$narrow, Flags>> for Fast<$wide, Flags> {
+            #[inline(always)]
+            fn from(x: Fast<$narrow, Flags>) -> Self {
+                Fast(x.0 as $wide, PhantomData)
+            }
+        }
+
+        impl_widen!(@op [$($c)?] $narrow => $wide: Add, add);
+        impl_widen!(@op [$($c)?] $narrow => $wide: Sub, sub);
+        impl_widen!(@op [$($c)?] $narrow => $wide: Mul, mul);
+        impl_widen!(@op [$($c)?] $narrow => $wide: Div, div);
+        impl_widen!(@op [$($c)?] $narrow => $wide: Rem, rem);
+
+        impl_widen!([$($c)?] $($rest)*);
+    };
+    (@op [$($c:tt)?] $narrow:ident => $wide:ident: $name:ident, $method:ident) => {
+        // Fast<$wide> + Fast<$narrow>
+        impl<Flags: $([$c])? FlagSet> $($c)? $name<Fast<$narrow, Flags>> for Fast<$wide, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: Fast<$narrow, Flags>) -> Self::Output {
+                self.$method(rhs.0 as $wide)
+            }
+        }
+
+        // Fast<$narrow> + Fast<$wide>
+        impl<Flags: $([$c])? FlagSet> $($c)? $name<Fast<$wide, Flags>> for Fast<$narrow, Flags> {
+            type Output = Fast<$wide, Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: Fast<$wide, Flags>) -> Self::Output {
+                Fast::<$wide, Flags>(self.0 as $wide, PhantomData).$method(rhs)
+            }
+        }
+    };
+}
+
+with_constness!(impl_widen! {
+    f32 => f64;
+});
+
+#[cfg(all(nightly, feature = "f16"))]
+with_constness!(impl_widen! {
+    f16 => f32;
+    f16 => f64;
+});
+
+#[cfg(all(nightly, feature = "f128"))]
+with_constness!(impl_widen! {
+    f32 => f128;
+    f64 => f128;
+});
+
+#[cfg(all(nightly, feature = "f16", feature = "f128"))]
+with_constness!(impl_widen! {
+    f16 => f128;
+});
+
+macro_rules! impl_narrow {
+    ($($wide:ident => $narrow:ident: $method:ident;)*) => {
+        $(
+        impl<Flags: FlagSet> Fast<$wide, Flags> {
+            #[doc = concat!("Round to the nearest `", stringify!($narrow), "`, ties to even")]
+            ///
+            /// Values too small for the narrower type round to zero or a subnormal. Values
+            /// beyond its range round to infinity, which is invalid for
+            /// [`AllFast`](crate::flags::AllFast); the `sanitize` feature catches that.
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            pub fn $method(self) -> Fast<$narrow, Flags> {
+                let result = self.0 as $narrow;
+                #[cfg(feature = "sanitize")]
+                crate::sanitize::<_, Flags>(stringify!($method), "result", result);
+                Fast(result, PhantomData)
+            }
+        }
+        )*
+    };
+}
+
+impl_narrow! {
+    f64 => f32: to_ff32;
+}
+
+#[cfg(all(nightly, feature = "f16"))]
+impl_narrow! {
+    f32 => f16: to_ff16;
+    f64 => f16: to_ff16;
+}
+
+#[cfg(all(nightly, feature = "f128"))]
+impl_narrow! {
+    f128 => f32: to_ff32;
+    f128 => f64: to_ff64;
+}
+
+#[cfg(all(nightly, feature = "f16", feature = "f128"))]
+impl_narrow! {
+    f128 => f16: to_ff16;
+}
+
+macro_rules! impl_from_int {
+    ([$($c:tt)?]) => {};
+    ([$($c:tt)?] $f:ident: ; $($rest:tt)*) => {
+        impl_from_int!([$($c)?] $($rest)*);
+    };
+    ([$($c:tt)?] $f:ident: $i:ident $($is:ident)*; $($rest:tt)*) => {
+        /// Exact, since every value of the integer type is representable
+        impl<Flags> $($c)? From<$i> for Fast<$f, Flags> {
+            #[inline(always)]
+            fn from(x: $i) -> Self {
+                Fast(x as $f, PhantomData)
+            }
+        }
+
+        impl_from_int!([$($c)?] $f: $($is)*; $($rest)*);
+    };
+}
+
+with_constness!(impl_from_int! {
+    f32: i8 u8 i16 u16;
+    f64: i8 u8 i16 u16 i32 u32;
+});
+
+#[cfg(all(nightly, feature = "f16"))]
+with_constness!(impl_from_int! {
+    f16: i8 u8;
+});
+
+#[cfg(all(nightly, feature = "f128"))]
+with_constness!(impl_from_int! {
+    f128: i8 u8 i16 u16 i32 u32 i64 u64;
+});
+
+// Rounding conversions from the integers that `From` leaves out
+macro_rules! impl_from_int_rounded {
+    ($($f:ident: $($i:ident $method:ident)*;)*) => {
+        $(
+        /// Rounding integer conversions
+        ///
+        /// These are not `From`, since integers beyond the precision of the float type round to
+        /// the nearest value, ties to even, like `as`. The result is always finite.
+        impl<Flags> Fast<$f, Flags> {
+            $(
+            #[doc = concat!("`x` rounded to the nearest `", stringify!($f), "`")]
+            #[inline(always)]
+            pub const fn $method(x: $i) -> Self {
+                Fast(x as $f, PhantomData)
+            }
+            )*
+        }
+        )*
+    };
+}
+
+impl_from_int_rounded! {
+    f32: i32 from_i32 u32 from_u32 i64 from_i64 u64 from_u64;
+    f64: i64 from_i64 u64 from_u64;
+}
+
+/// A float type that converts to the integer type `I`, see [`Fast::to_int_unchecked`].
+///
+/// This trait is sealed and can not be implemented outside of this crate.
+pub trait FloatToInt<I>: FastFloat + private::Sealed<I> {
+    #[doc(hidden)]
+    unsafe fn to_int_unchecked(self) -> I;
+    /// Whether the value is finite, and in the range of `I` once truncated
+    #[doc(hidden)]
+    fn fits_int(self) -> bool;
+}
+
+macro_rules! impl_float_to_int {
+    ($($f:ident: $($i:ident)*;)*) => {
+        $($(
+        impl private::Sealed<$i> for $f {}
+
+        impl FloatToInt<$i> for $f {
+            #[inline(always)]
+            unsafe fn to_int_unchecked(self) -> $i {
+                #[cfg(nightly)]
+                {
+                    unsafe { float_to_int_unchecked(self) }
+                }
+                #[cfg(not(nightly))]
+                {
+                    unsafe { <$f>::to_int_unchecked(self) }
+                }
+            }
+
+            #[inline]
+            fn fits_int(self) -> bool {
+                // `MIN` and `MAX + 1` are powers of two, or `MAX` already rounded up to one, or
+                // infinite for `f16`. Near `MIN` the subtraction is exact, and it is NaN if
+                // `self` is infinite too.
+                let (min, max) = ($i::MIN as $f, $i::MAX as $f + 1.);
+                self - min > -1. && self < max
+            }
+        }
+        )*)*
+    };
+}
+
+impl_float_to_int! {
+    f32: i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize;
+    f64: i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize;
+}
+
+#[cfg(all(nightly, feature = "f16"))]
+impl_float_to_int! {
+    f16: i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize;
+}
+
+#[cfg(all(nightly, feature = "f128"))]
+impl_float_to_int! {
+    f128: i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize;
+}
+
+/// Integer conversions
+impl<F: FastFloat, Flags> Fast<F, Flags> {
+    /// Round towards zero and convert to the integer type `I`, without the saturating checks
+    /// of an `as` cast
+    ///
+    /// # Safety
+    ///
+    /// Like [`f64::to_int_unchecked`], the value must be finite and, once truncated, in the
+    /// range of `I`. Fast values with the [`AllFast`](crate::flags::AllFast) flags are assumed
+    /// to be finite already, but the range is not checked.
+    #[inline(always)]
+    pub unsafe fn to_int_unchecked<I>(self) -> I
+    where
+        F: FloatToInt<I>,
+    {
+        unsafe { self.0.to_int_unchecked() }
+    }
+
+    /// [`Fast::to_int_unchecked`], with the safety requirements checked in debug builds
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Fast::to_int_unchecked`]; in release builds nothing is checked.
+    ///
+    /// # Panics
+    ///
+    /// With debug assertions, if the value is not finite or out of the range of `I`.
+    #[inline(always)]
+    #[track_caller]
+    pub unsafe fn to_int_debug_checked<I>(self) -> I
+    where
+        F: FloatToInt<I>,
+    {
+        debug_assert!(
+            self.0.fits_int(),
+            "fast-floats: {:?} does not fit in `{}`",
+            self.0,
+            type_name::<I>()
+        );
+        unsafe { self.0.to_int_unchecked() }
+    }
+}
+
+mod private {
+    pub trait Sealed<I> {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::flags::Strict;
+    use crate::{Fast, FF32, FF64};
+
+    #[test]
+    fn widen() {
+        assert_eq!(FF64::from(FF32::from(0.1)), FF64::from(0.1f32 as f64));
+        assert_ne!(FF64::from(FF32::from(0.1)), FF64::from(0.1));
+        let tiny = f32::from_bits(1);
+        assert_eq!(*FF64::from(FF32::from(tiny)), 2f64.powi(-149));
+    }
+
+    #[test]
+    fn to_ff32_rounding() {
+        let narrow = |x: f64| *Fast::<f64, Strict>::from(x).to_ff32();
+        assert_eq!(narrow(0.1), 0.1f32);
+        assert_eq!(narrow(-2.5), -2.5f32);
+        // halfway between 1 and the next f32 rounds to even, anything above rounds up
+        let half_ulp = 2f64.powi(-24);
+        assert_eq!(narrow(1. + half_ulp), 1.);
+        assert_eq!(narrow(1. + half_ulp + 2f64.powi(-40)), 1. + f32::EPSILON);
+        assert_eq!(narrow(1. + 3. * half_ulp), 1. + 2. * f32::EPSILON);
+        assert_eq!(narrow(1. - half_ulp / 2.), 1.);
+        // subnormals, underflow and overflow
+        assert_eq!(narrow(2f64.powi(-149)), f32::from_bits(1));
+        assert_eq!(narrow(2f64.powi(-151)), 0.);
+        assert_eq!(narrow(-1e-300).to_bits(), (-0f32).to_bits());
+        assert_eq!(narrow(f32::MAX as f64), f32::MAX);
+        assert_eq!(narrow(f64::MAX), f32::INFINITY);
+        // round trip of every f32 is exact
+        for bits in (0..u32::MAX).step_by(65537) {
+            let x = f32::from_bits(bits);
+            if x.is_finite() {
+                assert_eq!(narrow(x as f64).to_bits(), bits);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "result of `to_ff32` is invalid: value is infinite")]
+    fn sanitize_to_ff32() {
+        let _ = FF64::from(1e300).to_ff32();
+    }
+
+    #[test]
+    fn mixed_ops() {
+        let (x, y) = (FF64::from(3.), FF32::from(2.));
+        assert_eq!(x + y, FF64::from(5.));
+        assert_eq!(x - y, FF64::from(1.));
+        assert_eq!(y * x, FF64::from(6.));
+        assert_eq!(y / x, FF64::from(2f64 / 3.));
+        assert_eq!(x % y, FF64::from(1.));
+
+        // f32 products accumulated in f64
+        let xs = [0.1f32; 1000].map(FF32::from);
+        let mut acc = FF64::ZERO;
+        let mut acc32 = FF32::ZERO;
+        for &x in &xs {
+            acc += x * x;
+            acc32 += x * x;
+        }
+        let exact = 1000. * (0.1f32 * 0.1f32) as f64;
+        assert!((*acc - exact).abs() < 1e-12);
+        assert!((*acc32 as f64 - exact).abs() > 1e-6);
+        assert_eq!(acc.to_ff32(), FF32::from(exact as f32));
+    }
+
+    #[test]
+    fn from_int() {
+        assert_eq!(FF32::from(-128i8), FF32::from(-128.));
+        assert_eq!(FF32::from(u16::MAX), FF32::from(65535.));
+        assert_eq!(FF64::from(i32::MIN), FF64::from(-2147483648.));
+        assert_eq!(FF64::from(u32::MAX), FF64::from(4294967295.));
+        assert_eq!(Fast::<f64, Strict>::from(7u8) / 2., Fast::from(3.5));
+
+        assert_eq!(FF32::from_i32(1 << 24 | 1), FF32::from(16777216.));
+        assert_eq!(FF32::from_i32(1 << 24 | 3), FF32::from(16777220.));
+        assert_eq!(FF32::from_i32(i32::MIN), FF32::from(-2147483648.));
+        assert_eq!(FF32::from_u32(u32::MAX), FF32::from(4294967296.));
+        assert_eq!(FF32::from_u64(u64::MAX), FF32::from(18446744073709551616.));
+        assert_eq!(FF64::from_i64(i64::MAX), FF64::from(9223372036854775808.));
+        assert_eq!(FF64::from_u64(1 << 53 | 1), FF64::from(9007199254740992.));
+        const X: FF32 = FF32::from_u32(7);
+        assert_eq!(X, FF32::from(7.));
+    }
+
+    #[test]
+    fn to_int_unchecked() {
+        unsafe {
+            assert_eq!(FF64::from(2147483647.9).to_int_unchecked::<i32>(), i32::MAX);
+            assert_eq!(
+                FF64::from(-2147483648.9).to_int_unchecked::<i32>(),
+                i32::MIN
+            );
+            assert_eq!(FF64::from(-0.9).to_int_unchecked::<u8>(), 0);
+            assert_eq!(FF32::from(255.5).to_int_unchecked::<u8>(), 255);
+            assert_eq!(FF32::from(-3.7).to_int_unchecked::<i64>(), -3);
+            assert_eq!(
+                FF64::from(-(2f64.powi(63))).to_int_unchecked::<i64>(),
+                i64::MIN
+            );
+            assert_eq!(FF32::MAX.to_int_unchecked::<u128>(), f32::MAX as u128);
+            assert_eq!(
+                FF64::from(1e10).to_int_debug_checked::<u64>(),
+                10_000_000_000
+            );
+        }
+    }
+
+    #[test]
+    fn fits_int() {
+        use super::FloatToInt;
+
+        fn fits<F: FloatToInt<I>, I>(xs: &[F]) -> bool {
+            xs.iter().all(|&x| x.fits_int())
+        }
+        fn misfits<F: FloatToInt<I>, I>(xs: &[F]) -> bool {
+            !xs.iter().any(|&x| x.fits_int())
+        }
+        let max_below = |x: f32| f32::from_bits(x.to_bits() - 1);
+
+        assert!(fits::<f64, i8>(&[127.99, -128.99, 0., -0.]));
+        assert!(misfits::<f64, i8>(&[128., -129., f64::NAN, f64::INFINITY]));
+        assert!(fits::<f64, u8>(&[255.99, -0.99]));
+        assert!(misfits::<f64, u8>(&[256., -1., f64::NEG_INFINITY]));
+        assert!(fits::<f64, i32>(&[2147483647.99, -2147483648.99]));
+        assert!(misfits::<f64, i32>(&[2147483648., -2147483649.]));
+        assert!(fits::<f32, i32>(&[
+            max_below(2f32.powi(31)),
+            -(2f32.powi(31))
+        ]));
+        assert!(misfits::<f32, i32>(&[
+            2f32.powi(31),
+            -max_below(2f32.powi(31)) * 2.
+        ]));
+        assert!(fits::<f64, i64>(&[-(2f64.powi(63)), 9223372036854774784.]));
+        assert!(misfits::<f64, i64>(&[
+            2f64.powi(63),
+            -(2f64.powi(63)) * (1. + f64::EPSILON)
+        ]));
+        assert!(fits::<f32, u64>(&[max_below(2f32.powi(64))]));
+        assert!(misfits::<f32, u64>(&[2f32.powi(64), -1.]));
+        assert!(fits::<f32, u128>(&[f32::MAX]));
+        assert!(misfits::<f32, u128>(&[f32::INFINITY, f32::NAN]));
+        assert!(fits::<f64, i128>(&[-(2f64.powi(127))]));
+        assert!(misfits::<f64, i128>(&[2f64.powi(127), f64::MAX]));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "fast-floats: 256.0 does not fit in `u8`")]
+    fn to_int_debug_checked() {
+        let _ = unsafe { FF32::from(256.).to_int_debug_checked::<u8>() };
+    }
+
+    #[cfg(all(nightly, feature = "f16"))]
+    #[test]
+    fn half() {
+        use super::FloatToInt;
+        use crate::FF16;
+
+        let x = FF16::from(0.1);
+        assert_eq!(FF32::from(x), FF32::from(0.1f16 as f32));
+        assert_eq!(FF64::from(x), FF64::from(0.1f16 as f64));
+        // 2049 is halfway between 2048 and 2050, rounds to even
+        assert_eq!(FF32::from(2049.).to_ff16(), FF16::from(2048.));
+        assert_eq!(FF64::from(2051.).to_ff16(), FF16::from(2052.));
+        assert_eq!(*Fast::<f32, Strict>::from(65520.).to_ff16(), f16::INFINITY);
+        assert_eq!(*FF32::from(1e-8).to_ff16(), 0.);
+
+        // half precision weights, accumulated in f32
+        let weights = [0.1f16; 100].map(FF16::from);
+        let mut acc = FF32::ZERO;
+        let mut acc16 = FF16::ZERO;
+        for &w in &weights {
+            acc += w * w;
+            acc16 += w * w;
+        }
+        assert!((*acc - 100. * (0.1f16 * 0.1f16) as f32).abs() < 1e-5);
+        assert!((*acc16 as f32 - *acc).abs() > 1e-3);
+        assert_eq!(x * FF64::from(2.), FF64::from(0.1f16 as f64 * 2.));
+
+        assert_eq!(FF16::from(-128i8), FF16::from(-128.));
+        assert_eq!(FF16::from(255u8), FF16::from(255.));
+        assert_eq!(unsafe { FF16::MAX.to_int_unchecked::<i32>() }, 65504);
+        assert_eq!(unsafe { FF16::from(-1.5).to_int_unchecked::<i8>() }, -1);
+        assert!(FloatToInt::<u16>::fits_int(65504f16));
+        assert!(!FloatToInt::<i16>::fits_int(32768f16));
+        assert!(!FloatToInt::<i32>::fits_int(f16::NEG_INFINITY));
+        assert!(!FloatToInt::<u128>::fits_int(f16::INFINITY));
+    }
+
+    #[cfg(all(nightly, feature = "f128"))]
+    #[test]
+    fn quad() {
+        use super::FloatToInt;
+        use crate::FF128;
+
+        let x = FF128::from(FF64::from(0.1));
+        assert_eq!(*x, 0.1f64 as f128);
+        assert_eq!(x.to_ff64(), FF64::from(0.1));
+        assert_eq!(FF128::from(FF32::from(0.1)).to_ff32(), FF32::from(0.1));
+        // halfway between 1 and the next f64 rounds to even
+        let half_ulp = FF128::from(2.).powi(-53);
+        assert_eq!(*(FF128::ONE + half_ulp).to_ff64(), 1.);
+        assert_eq!(
+            *(FF128::ONE + half_ulp * 3.).to_ff64(),
+            1. + 2. * f64::EPSILON
+        );
+
+        assert_eq!(FF64::from(0.5) + FF128::from(0.25), FF128::from(0.75));
+        assert_eq!(FF128::from(i64::MIN), FF128::from(-(2f128.powi(63))));
+        assert_eq!(FF128::from(u64::MAX) + 1., FF128::from(2f128.powi(64)));
+        let big = FF128::from(u64::MAX) * FF128::from(u32::MAX);
+        assert_eq!(
+            unsafe { big.to_int_unchecked::<u128>() },
+            u64::MAX as u128 * u32::MAX as u128
+        );
+        assert!(FloatToInt::<i128>::fits_int(-(2f128.powi(127))));
+        assert!(!FloatToInt::<i128>::fits_int(2f128.powi(127)));
+    }
+
+    #[cfg(nightly)]
+    #[test]
+    fn const_widen() {
+        const X: FF64 = FF64::ONE + FF32::EPSILON;
+        assert_eq!(*X, 1. + f32::EPSILON as f64);
+    }
+}
@@ -0,0 +1,40 @@
+//! Errors from the checked constructors.
+
+use std::fmt;
+
+/// The invariant a float value failed to uphold for `Fast`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InvalidFloat {
+    /// The value is NaN.
+    Nan,
+    /// The value is positive or negative infinity.
+    Infinite,
+}
+
+impl fmt::Display for InvalidFloat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidFloat::Nan => f.write_str("value is NaN"),
+            InvalidFloat::Infinite => f.write_str("value is infinite"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidFloat {}
+
+/// The first element of a slice that failed to uphold the invariants of `Fast`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidElement {
+    /// Index of the element in the slice.
+    pub index: usize,
+    /// The invariant the element failed.
+    pub error: InvalidFloat,
+}
+
+impl fmt::Display for InvalidElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "element {}: {}", self.index, self.error)
+    }
+}
+
+impl std::error::Error for InvalidElement {}
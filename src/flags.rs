@@ -0,0 +1,123 @@
+//! Type-level fast-math flag sets.
+//!
+//! The second type parameter of [`Fast`](crate::Fast) selects which of the LLVM
+//! [fast-math flags][1] its operators are allowed to use. Each flag set is a marker type
+//! and maps to the family of intrinsics that enables exactly (or at most) those flags:
+//!
+//! | Flag set      | LLVM flags                                | Operations            |
+//! |---------------|-------------------------------------------|-----------------------|
+//! | [`AllFast`]   | `nnan ninf nsz arcp contract afn reassoc` | `fadd_fast` etc.      |
+//! | [`Algebraic`] | `nsz arcp contract afn reassoc`           | `fadd_algebraic` etc. |
+//! | [`Strict`]    | none                                      | `+ - * / %`           |
+//!
+//! [`Fast::mul_add`](crate::Fast::mul_add) uses `fmuladd`, which fuses only where that is
+//! faster, with the `contract` flag and the always fused `fma` with [`Strict`].
+//!
+//! [`AllFast`] is the default, so `Fast<f64>` behaves like it always did.
+//!
+//! With the `strict` crate feature, every flag set uses the plain IEEE operations.
+//!
+//! [1]: https://llvm.org/docs/LangRef.html#fast-math-flags
+
+use crate::FastFloat;
+
+/// All fast-math flags, including `nnan` and `ninf`.
+///
+/// Operations on NaN or infinite values are undefined behaviour with this flag set.
+pub enum AllFast {}
+
+/// The algebraic flags: reassociation, contraction, reciprocals, no signed zeros and
+/// approximate functions.
+///
+/// NaN and infinities may produce unspecified results, but never undefined behaviour.
+pub enum Algebraic {}
+
+/// No fast-math flags, plain IEEE arithmetic.
+pub enum Strict {}
+
+macro_rules! flag_set {
+    ([$($c:tt)?]) => {
+        /// A set of fast-math flags, see the [module documentation](self).
+        ///
+        /// This trait is sealed and can not be implemented outside of this crate.
+        pub $($c)? trait FlagSet: private::Sealed {
+            /// Whether the flags include `nnan` and `ninf`
+            #[doc(hidden)]
+            const ASSUMES_FINITE: bool;
+            /// Whether the flags include `reassoc`
+            #[doc(hidden)]
+            const REASSOCIATE: bool;
+            #[doc(hidden)]
+            unsafe fn add<F: $([$c])? FastFloat>(a: F, b: F) -> F;
+            #[doc(hidden)]
+            unsafe fn sub<F: $([$c])? FastFloat>(a: F, b: F) -> F;
+            #[doc(hidden)]
+            unsafe fn mul<F: $([$c])? FastFloat>(a: F, b: F) -> F;
+            #[doc(hidden)]
+            unsafe fn div<F: $([$c])? FastFloat>(a: F, b: F) -> F;
+            #[doc(hidden)]
+            unsafe fn rem<F: $([$c])? FastFloat>(a: F, b: F) -> F;
+            #[doc(hidden)]
+            fn mul_add<F: $([$c])? FastFloat>(a: F, b: F, c: F) -> F;
+        }
+    };
+}
+
+with_constness!(flag_set! {});
+
+macro_rules! impl_flag_set {
+    ([$($c:tt)?]) => {};
+    ([$($c:tt)?] $flags:ident($finite:expr, $reassociate:expr): $add:ident, $sub:ident, $mul:ident, $div:ident, $rem:ident, $mul_add:ident; $($rest:tt)*) => {
+        impl private::Sealed for $flags {}
+
+        impl $($c)? FlagSet for $flags {
+            const ASSUMES_FINITE: bool = $finite;
+            const REASSOCIATE: bool = $reassociate;
+            #[inline(always)]
+            unsafe fn add<F: $([$c])? FastFloat>(a: F, b: F) -> F {
+                F::$add(a, b)
+            }
+            #[inline(always)]
+            unsafe fn sub<F: $([$c])? FastFloat>(a: F, b: F) -> F {
+                F::$sub(a, b)
+            }
+            #[inline(always)]
+            unsafe fn mul<F: $([$c])? FastFloat>(a: F, b: F) -> F {
+                F::$mul(a, b)
+            }
+            #[inline(always)]
+            unsafe fn div<F: $([$c])? FastFloat>(a: F, b: F) -> F {
+                F::$div(a, b)
+            }
+            #[inline(always)]
+            unsafe fn rem<F: $([$c])? FastFloat>(a: F, b: F) -> F {
+                F::$rem(a, b)
+            }
+            #[inline(always)]
+            fn mul_add<F: $([$c])? FastFloat>(a: F, b: F, c: F) -> F {
+                F::$mul_add(a, b, c)
+            }
+        }
+
+        impl_flag_set!([$($c)?] $($rest)*);
+    };
+}
+
+#[cfg(not(feature = "strict"))]
+with_constness!(impl_flag_set! {
+    AllFast(true, true): fadd_fast, fsub_fast, fmul_fast, fdiv_fast, frem_fast, fmuladd;
+    Algebraic(false, true): fadd_algebraic, fsub_algebraic, fmul_algebraic, fdiv_algebraic, frem_algebraic, fmuladd;
+    Strict(false, false): add, sub, mul, div, rem, fma;
+});
+
+// With the `strict` feature every flag set uses plain IEEE arithmetic.
+#[cfg(feature = "strict")]
+with_constness!(impl_flag_set! {
+    AllFast(true, false): add, sub, mul, div, rem, fma;
+    Algebraic(false, false): add, sub, mul, div, rem, fma;
+    Strict(false, false): add, sub, mul, div, rem, fma;
+});
+
+mod private {
+    pub trait Sealed {}
+}
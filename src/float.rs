@@ -0,0 +1,320 @@
+//! The float types supported by `Fast`.
+
+use std::fmt;
+#[cfg(all(nightly, feature = "f128"))]
+use std::intrinsics::{
+    ceilf128, cosf128, exp2f128, expf128, floorf128, fmaf128, fmuladdf128, log10f128, log2f128,
+    logf128, powf128, powif128, roundf128, sinf128, sqrtf128, truncf128,
+};
+#[cfg(all(nightly, feature = "f16"))]
+use std::intrinsics::{
+    ceilf16, cosf16, exp2f16, expf16, floorf16, fmaf16, fmuladdf16, log10f16, log2f16, logf16,
+    powf16, powif16, roundf16, sinf16, sqrtf16, truncf16,
+};
+#[cfg(nightly)]
+use std::intrinsics::{
+    ceilf32, ceilf64, cosf32, cosf64, exp2f32, exp2f64, expf32, expf64, fadd_algebraic, fadd_fast,
+    fdiv_algebraic, fdiv_fast, floorf32, floorf64, fmaf32, fmaf64, fmul_algebraic, fmul_fast,
+    fmuladdf32, fmuladdf64, frem_algebraic, frem_fast, fsub_algebraic, fsub_fast, log10f32,
+    log10f64, log2f32, log2f64, logf32, logf64, powf32, powf64, powif32, powif64, roundf32,
+    roundf64, sinf32, sinf64, sqrtf32, sqrtf64, truncf32, truncf64,
+};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+#[cfg(not(nightly))]
+use fallback::*;
+
+/// Stand-ins for the intrinsics on stable: regular float operations.
+#[cfg(not(nightly))]
+mod fallback {
+    use std::ops::{Add, Div, Mul, Rem, Sub};
+
+    macro_rules! fallback {
+        ($($trait:ident, $op:tt: $fast:ident, $algebraic:ident;)*) => {
+            $(
+            #[inline(always)]
+            pub unsafe fn $fast<T: $trait<Output = T>>(a: T, b: T) -> T {
+                a $op b
+            }
+
+            #[inline(always)]
+            pub fn $algebraic<T: $trait<Output = T>>(a: T, b: T) -> T {
+                a $op b
+            }
+            )*
+        }
+    }
+
+    fallback! {
+        Add, +: fadd_fast, fadd_algebraic;
+        Sub, -: fsub_fast, fsub_algebraic;
+        Mul, *: fmul_fast, fmul_algebraic;
+        Div, /: fdiv_fast, fdiv_algebraic;
+        Rem, %: frem_fast, frem_algebraic;
+    }
+
+    // Unfused, since there is no fused multiply-add in core on stable
+    macro_rules! fallback_fma {
+        ($($f:ty: $($name:ident)*;)*) => {
+            $($(
+            #[inline(always)]
+            pub fn $name(a: $f, b: $f, c: $f) -> $f {
+                a * b + c
+            }
+            )*)*
+        }
+    }
+
+    fallback_fma! {
+        f32: fmaf32 fmuladdf32;
+        f64: fmaf64 fmuladdf64;
+    }
+}
+
+/// The limits and mathematical constants of the float types
+macro_rules! float_consts {
+    (@decl $($limit:ident)*; $($name:ident)*) => {
+        $(
+        #[doc(hidden)]
+        const $limit: Self;
+        )*
+        $(
+        #[doc(hidden)]
+        const $name: Self;
+        )*
+    };
+    ($f:ident: $($limit:ident)*; $($name:ident)*) => {
+        $(const $limit: Self = $f::$limit;)*
+        $(const $name: Self = std::$f::consts::$name;)*
+    };
+}
+
+macro_rules! fast_float {
+    ([$($c:tt)?]) => {
+        /// The float types `Fast` knows how to operate on: `f32` and `f64`, and `f16` and `f128`
+        /// with the crate features of the same name.
+        ///
+        /// All operators of [`Fast`](crate::Fast) are implemented generically over this trait, so
+        /// generic code can be written against `Fast<F>` with an `F: FastFloat` bound. On nightly
+        /// it is a `const` trait, so that the operators can be used in const evaluation.
+        ///
+        /// This trait is sealed and can not be implemented outside of this crate.
+        pub $($c)? trait FastFloat:
+            private::Sealed
+            + Copy
+            + PartialEq
+            + PartialOrd
+            + fmt::Debug
+            + $([$c])? Add<Output = Self>
+            + $([$c])? Sub<Output = Self>
+            + $([$c])? Mul<Output = Self>
+            + $([$c])? Div<Output = Self>
+            + $([$c])? Rem<Output = Self>
+            + $([$c])? Neg<Output = Self>
+        {
+            #[doc(hidden)]
+            const ZERO: Self;
+            #[doc(hidden)]
+            const ONE: Self;
+            float_consts!(@decl EPSILON MIN_POSITIVE MAX MIN; PI TAU E FRAC_PI_2 FRAC_PI_3 FRAC_PI_4 FRAC_PI_6 FRAC_PI_8 FRAC_1_PI FRAC_2_PI FRAC_2_SQRT_PI SQRT_2 FRAC_1_SQRT_2 LN_2 LN_10 LOG2_E LOG2_10 LOG10_E LOG10_2);
+            #[doc(hidden)]
+            unsafe fn fadd_fast(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            unsafe fn fsub_fast(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            unsafe fn fmul_fast(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            unsafe fn fdiv_fast(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            unsafe fn frem_fast(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            fn fadd_algebraic(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            fn fsub_algebraic(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            fn fmul_algebraic(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            fn fdiv_algebraic(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            fn frem_algebraic(a: Self, b: Self) -> Self;
+            #[doc(hidden)]
+            fn is_nan(self) -> bool;
+            #[doc(hidden)]
+            fn is_infinite(self) -> bool;
+            #[doc(hidden)]
+            fn abs(self) -> Self;
+            #[doc(hidden)]
+            fn copysign(self, sign: Self) -> Self;
+            #[doc(hidden)]
+            fn hash_bits(self) -> u64;
+            #[doc(hidden)]
+            fn fma(a: Self, b: Self, c: Self) -> Self;
+            #[doc(hidden)]
+            fn fmuladd(a: Self, b: Self, c: Self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn sqrt(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn exp(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn exp2(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn ln(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn log2(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn log10(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn sin(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn cos(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn floor(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn ceil(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn round(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn trunc(self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn powf(self, n: Self) -> Self;
+            #[cfg(nightly)]
+            #[doc(hidden)]
+            fn powi(self, n: i32) -> Self;
+        }
+    };
+}
+
+with_constness!(fast_float! {});
+
+// The math intrinsics can not be evaluated at compile time
+#[cfg(nightly)]
+macro_rules! unavailable {
+    ($name:ident) => {
+        panic!(concat!(
+            "`",
+            stringify!($name),
+            "` can not be used in const evaluation"
+        ))
+    };
+}
+
+macro_rules! impl_float {
+    ([$($c:tt)?]) => {};
+    ([$($c:tt)?] $f:ident, $fma:ident, $fmuladd:ident, $powf:ident, $powi:ident;
+        $($unary:ident: $intrinsic:ident)*; $($rounding:ident: $const_intrinsic:ident)*;
+        $($rest:tt)*) => {
+        impl private::Sealed for $f {}
+
+        impl $($c)? FastFloat for $f {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+            float_consts!($f: EPSILON MIN_POSITIVE MAX MIN; PI TAU E FRAC_PI_2 FRAC_PI_3 FRAC_PI_4 FRAC_PI_6 FRAC_PI_8 FRAC_1_PI FRAC_2_PI FRAC_2_SQRT_PI SQRT_2 FRAC_1_SQRT_2 LN_2 LN_10 LOG2_E LOG2_10 LOG10_E LOG10_2);
+            impl_float!(@fast $f: fadd_fast +, fsub_fast -, fmul_fast *, fdiv_fast /, frem_fast %);
+            #[inline(always)]
+            fn fadd_algebraic(a: Self, b: Self) -> Self { fadd_algebraic(a, b) }
+            #[inline(always)]
+            fn fsub_algebraic(a: Self, b: Self) -> Self { fsub_algebraic(a, b) }
+            #[inline(always)]
+            fn fmul_algebraic(a: Self, b: Self) -> Self { fmul_algebraic(a, b) }
+            #[inline(always)]
+            fn fdiv_algebraic(a: Self, b: Self) -> Self { fdiv_algebraic(a, b) }
+            #[inline(always)]
+            fn frem_algebraic(a: Self, b: Self) -> Self { frem_algebraic(a, b) }
+            #[inline(always)]
+            fn is_nan(self) -> bool { <$f>::is_nan(self) }
+            #[inline(always)]
+            fn is_infinite(self) -> bool { <$f>::is_infinite(self) }
+            #[inline(always)]
+            fn abs(self) -> Self { <$f>::abs(self) }
+            #[inline(always)]
+            fn copysign(self, sign: Self) -> Self { <$f>::copysign(self, sign) }
+            #[inline(always)]
+            fn hash_bits(self) -> u64 {
+                let bits = <$f>::to_bits(self) as u128;
+                (bits ^ (bits >> 64)) as u64
+            }
+            #[inline(always)]
+            fn fma(a: Self, b: Self, c: Self) -> Self { $fma(a, b, c) }
+            #[inline(always)]
+            fn fmuladd(a: Self, b: Self, c: Self) -> Self { $fmuladd(a, b, c) }
+            #[cfg(nightly)]
+            #[inline(always)]
+            fn powf(self, n: Self) -> Self {
+                const_select!((x: $f = self, n: $f = n) -> $f, unavailable!(powf), $powf(x, n))
+            }
+            #[cfg(nightly)]
+            #[inline(always)]
+            fn powi(self, n: i32) -> Self {
+                const_select!((x: $f = self, n: i32 = n) -> $f, unavailable!(powi), $powi(x, n))
+            }
+            $(
+            #[cfg(nightly)]
+            #[inline(always)]
+            fn $unary(self) -> Self {
+                const_select!((x: $f = self) -> $f, unavailable!($unary), $intrinsic(x))
+            }
+            )*
+            $(
+            #[cfg(nightly)]
+            #[inline(always)]
+            fn $rounding(self) -> Self { $const_intrinsic(self) }
+            )*
+        }
+
+        impl_float!([$($c)?] $($rest)*);
+    };
+    // The fast intrinsics are not `const`, so the strict operation is used during const
+    // evaluation
+    (@fast $f:ident: $($name:ident $op:tt),*) => {
+        $(
+        #[inline(always)]
+        unsafe fn $name(a: Self, b: Self) -> Self {
+            const_select!((a: $f = a, b: $f = b) -> $f, a $op b, unsafe { $name(a, b) })
+        }
+        )*
+    };
+}
+
+with_constness!(impl_float! {
+    f32, fmaf32, fmuladdf32, powf32, powif32;
+        sqrt: sqrtf32 exp: expf32 exp2: exp2f32 ln: logf32 log2: log2f32 log10: log10f32
+        sin: sinf32 cos: cosf32;
+        floor: floorf32 ceil: ceilf32 round: roundf32 trunc: truncf32;
+    f64, fmaf64, fmuladdf64, powf64, powif64;
+        sqrt: sqrtf64 exp: expf64 exp2: exp2f64 ln: logf64 log2: log2f64 log10: log10f64
+        sin: sinf64 cos: cosf64;
+        floor: floorf64 ceil: ceilf64 round: roundf64 trunc: truncf64;
+});
+
+#[cfg(all(nightly, feature = "f16"))]
+with_constness!(impl_float! {
+    f16, fmaf16, fmuladdf16, powf16, powif16;
+        sqrt: sqrtf16 exp: expf16 exp2: exp2f16 ln: logf16 log2: log2f16 log10: log10f16
+        sin: sinf16 cos: cosf16;
+        floor: floorf16 ceil: ceilf16 round: roundf16 trunc: truncf16;
+});
+
+#[cfg(all(nightly, feature = "f128"))]
+with_constness!(impl_float! {
+    f128, fmaf128, fmuladdf128, powf128, powif128;
+        sqrt: sqrtf128 exp: expf128 exp2: exp2f128 ln: logf128 log2: log2f128 log10: log10f128
+        sin: sinf128 cos: cosf128;
+        floor: floorf128 ceil: ceilf128 round: roundf128 trunc: truncf128;
+});
+
+mod private {
+    pub trait Sealed {}
+}
@@ -3,6 +3,28 @@
 //!
 //! **Changes from original project:**
 //! - Fast floats implement deref to their source float type
+//! - The fast-math flags are selected at the type level, see [`flags`]
+//! - [`Algebraic`] is a safe wrapper using only the algebraic flags
+//! - Checked constructors [`Fast::try_new`] and [`Fast::check_slice`]
+//! - A `sanitize` feature that checks the operands and result of every operation
+//! - A `strict` feature that turns off fast-math for the whole build
+//! - The operators are implemented generically over [`FastFloat`]
+//! - Multi-accumulator slice reductions in [`reduce`]
+//! - Zero-copy conversions between `[F]` and `[Fast<F>]`, see [`Fast::from_slice`]
+//! - Explicit SIMD lanes with `Fast<Simd<F, N>>`
+//! - Fixed-size vectors `Fast<[F; N]>` with element-wise operators
+//! - Fused multiply-add [`Fast::mul_add`] and polynomial evaluation [`Fast::poly_eval`]
+//! - Math functions (`sqrt`, `exp`, `ln`, `sin`, `floor`, ...) on `Fast` that work in `no_std`
+//! - Approximate transcendental functions with selectable accuracy in [`approx`]
+//! - `Eq`, `Ord` and `Hash` with the [`AllFast`] flags, and `min`/`max` without NaN handling
+//! - Constants such as `FF64::PI` and `FF32::EPSILON`, and `Default`, usable in `const`
+//! - Operators usable in `const` on nightly, to build lookup tables at compile time
+//! - Conversions between `FF32` and `FF64`, and mixed-precision operators
+//! - Integer conversions: `From` where exact, rounding ones like [`Fast::from_i32`], and
+//!   [`Fast::to_int_unchecked`] without the saturating checks
+//! - Half and quad precision `FF16` and `FF128`, behind crate features
+//! - A bfloat16 storage type [`BF16`] that computes through `FF32`
+//! - Complex numbers [`FastComplex`] with fast-math operators and dot products in [`complex`]
 //!
 //! # Original docs
 //! [Docs for `Fast` struct ](https://docs.rs/fast-floats/latest/fast_floats/struct.Fast.html)
@@ -19,57 +41,321 @@
 //!
 //! [1]: https://llvm.org/docs/LangRef.html#fast-math-flags
 //!
+//! # Crate Features
+//!
+//! - `sanitize`: every operator checks its operands and result, and panics with the
+//!   caller's location if a NaN or infinity is passed to or produced by an operation with
+//!   the [`AllFast`] flags. Off by default; without it the operators are
+//!   unchanged.
+//! - `strict`: every operator compiles to the ordinary `+ - * / %`, regardless of the
+//!   flag set, without changing the public API. Useful to bisect numerical differences
+//!   caused by fast-math.
+//! - `alloc`: conversions between `Vec<F>` / `Box<[F]>` and their `Fast` counterparts.
+//! - `simd`: `Fast<Simd<F, N>>` vectors, see the `simd` module. Nightly only.
+//! - `f16`, `f128`: `FF16` and `FF128`, with the operators and conversions of the other
+//!   float types. Nightly only. `f128` has no `Display` in `core`, so neither has `FF128`.
+//!
 //! # Rust Version
 //!
-//! This crate is nightly only and experimental. Breaking changes can occur at
+//! This crate is experimental. Breaking changes can occur at
 //! any time, if changes in Rust require it.
+//!
+//! The fast-math intrinsics are nightly only. On a stable compiler, which the build script
+//! detects automatically, `Fast` falls back to regular float operations for every flag set
+//! and `Deref` is not `const`. `mul_add` is not fused on stable, and the math functions
+//! are not available.
+//!
+//! On nightly the operators, `From`, `Default`, `try_new` and `check_slice` are `const`; using
+//! them in a `const` item needs `#![feature(const_trait_impl, const_ops)]`. The intrinsics can
+//! not run at compile time, so const evaluation uses the strict operations for every flag set.
 #![no_std]
-#![feature(core_intrinsics, const_trait_impl)]
+#![cfg_attr(nightly, allow(internal_features))]
+#![cfg_attr(
+    nightly,
+    feature(
+        core_intrinsics,
+        const_trait_impl,
+        const_convert,
+        const_default,
+        const_ops,
+        const_eval_select
+    )
+)]
+#![cfg_attr(all(nightly, feature = "simd"), feature(portable_simd))]
+#![cfg_attr(all(nightly, feature = "f16"), feature(f16))]
+#![cfg_attr(all(nightly, feature = "f128"), feature(f128))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
 extern crate core as std;
 
-use std::intrinsics::{fadd_fast, fdiv_fast, fmul_fast, frem_fast, fsub_fast};
+// `impl const` and `[const]` bounds are nightly only syntax, so they must not be parsed on
+// stable: `$mac` is invoked with `[const]` on nightly and `[]` on stable, and writes
+// `impl $($c)? Trait` and `T: $([$c])? Trait`
+macro_rules! with_constness {
+    ($mac:ident! { $($body:tt)* }) => {
+        #[cfg(nightly)]
+        $mac! { [const] $($body)* }
+        #[cfg(not(nightly))]
+        $mac! { [] $($body)* }
+    };
+}
+
+// `$runtime`, except during const evaluation, where it is `$compiletime`. Both see the
+// arguments under the given names.
+#[cfg(nightly)]
+macro_rules! const_select {
+    (($($arg:ident: $ty:ty = $value:expr),*) -> $ret:ty, $compiletime:expr, $runtime:expr) => {{
+        #[allow(unused_variables)] // if it panics
+        const fn compiletime($($arg: $ty),*) -> $ret {
+            $compiletime
+        }
+        #[inline(always)]
+        fn runtime($($arg: $ty),*) -> $ret {
+            $runtime
+        }
+        std::intrinsics::const_eval_select(($($value,)*), compiletime, runtime)
+    }};
+}
+
+#[cfg(not(nightly))]
+macro_rules! const_select {
+    (($($arg:ident: $ty:ty = $value:expr),*) -> $ret:ty, $compiletime:expr, $runtime:expr) => {{
+        $(let $arg: $ty = $value;)*
+        $runtime
+    }};
+}
+
+// `F + $wrapper<F>` for each float type, forwarding to `Fast<F> + $wrapper<F>`, and the same
+// with references to either operand. Implemented per float type, since
+// `impl<F> Add<Fast<F>> for F` is not allowed
+macro_rules! impl_rev_op {
+    ([$($c:tt)?] $name:ident, $method:ident; $wrapper:ident) => {
+        impl_rev_op!(@float [$($c)?] $name, $method; $wrapper, f32);
+        impl_rev_op!(@float [$($c)?] $name, $method; $wrapper, f64);
+        #[cfg(all(nightly, feature = "f16"))]
+        impl_rev_op!(@float [$($c)?] $name, $method; $wrapper, f16);
+        #[cfg(all(nightly, feature = "f128"))]
+        impl_rev_op!(@float [$($c)?] $name, $method; $wrapper, f128);
+    };
+    (@float [$($c:tt)?] $name:ident, $method:ident; $wrapper:ident, $f:ty) => {
+        impl<Flags: $([$c])? FlagSet> $($c)? $name<$wrapper<$f, Flags>> for $f {
+            type Output = $wrapper<$f, Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: $wrapper<$f, Flags>) -> Self::Output {
+                Fast::<$f, Flags>(self, PhantomData).$method(rhs)
+            }
+        }
+
+        impl_ref_op!([$($c)?] $name, $method; [Flags: $([$c])? FlagSet] $f, $wrapper<$f, Flags>);
+    };
+}
+
+// &Lhs + Rhs, Lhs + &Rhs and &Lhs + &Rhs, forwarding to Lhs + Rhs
+macro_rules! impl_ref_op {
+    ([$($c:tt)?] $name:ident, $method:ident; [$($gen:tt)*] $lhs:ty, $rhs:ty $(where $($bound:tt)*)?) => {
+        impl<'a, $($gen)*> $($c)? $name<$rhs> for &'a $lhs $(where $($bound)*)? {
+            type Output = <$lhs as $name<$rhs>>::Output;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: $rhs) -> Self::Output {
+                <$lhs as $name<$rhs>>::$method(*self, rhs)
+            }
+        }
+
+        impl<'a, $($gen)*> $($c)? $name<&'a $rhs> for $lhs $(where $($bound)*)? {
+            type Output = <$lhs as $name<$rhs>>::Output;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: &'a $rhs) -> Self::Output {
+                <$lhs as $name<$rhs>>::$method(self, *rhs)
+            }
+        }
+
+        impl<'a, 'b, $($gen)*> $($c)? $name<&'a $rhs> for &'b $lhs $(where $($bound)*)? {
+            type Output = <$lhs as $name<$rhs>>::Output;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: &'a $rhs) -> Self::Output {
+                <$lhs as $name<$rhs>>::$method(*self, *rhs)
+            }
+        }
+    };
+}
+
+// `$wrapper<F> += Rhs` wherever `$wrapper<F> + Rhs` is implemented
+macro_rules! impl_assignop {
+    ([$($c:tt)?] $wrapper:ident;) => {};
+    ([$($c:tt)?] $wrapper:ident; $name:ident, $method:ident, $optrt:ident, $opmth:ident; $($rest:tt)*) => {
+        impl<F, Flags, Rhs> $($c)? $name<Rhs> for $wrapper<F, Flags>
+            where Self: $([$c])? $optrt<Rhs, Output=Self> + Copy,
+        {
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(&mut self, rhs: Rhs) {
+                *self = (*self).$opmth(rhs)
+            }
+        }
+
+        impl_assignop!([$($c)?] $wrapper; $($rest)*);
+    };
+}
+
+pub mod approx;
+mod array;
+mod bf16;
+pub mod complex;
+mod convert;
+mod error;
+pub mod flags;
+mod float;
+pub mod reduce;
+#[cfg(all(nightly, feature = "simd"))]
+pub mod simd;
+
+pub use bf16::BF16;
+pub use complex::FastComplex;
+pub use convert::FloatToInt;
+pub use error::{InvalidElement, InvalidFloat};
+use flags::{AllFast, FlagSet};
+pub use float::FastFloat;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+#[cfg(feature = "alloc")]
+use std::mem::ManuallyDrop;
 use std::ops::{
-    Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
+    Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub,
+    SubAssign,
 };
 
 /// “fast-math” wrapper for f32 and f64.
 ///
 /// The `Fast` type enforces no invariant and can hold any f32, f64 values.
 /// See crate docs for more details.
-#[derive(Copy, Clone, PartialEq, PartialOrd)]
+///
+/// `Flags` is the set of fast-math flags the operators use, see [`flags`].
 #[repr(transparent)]
-pub struct Fast<F>(F);
+pub struct Fast<F, Flags = AllFast>(F, PhantomData<Flags>);
 
-impl<F> const Deref for Fast<F> {
-    type Target = F;
+impl<F: Copy, Flags> Copy for Fast<F, Flags> {}
 
+impl<F: Clone, Flags> Clone for Fast<F, Flags> {
     #[inline(always)]
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    fn clone(&self) -> Self {
+        Fast(self.0.clone(), PhantomData)
     }
 }
 
-impl<F> DerefMut for Fast<F> {
+impl<F: PartialEq, Flags> PartialEq for Fast<F, Flags> {
     #[inline(always)]
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<F: PartialOrd, Flags> PartialOrd for Fast<F, Flags> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+/// [`AllFast`] values are assumed to never be NaN, see [`Fast::new`], so they have a total
+/// order. With the `sanitize` feature comparing a NaN panics.
+///
+/// The other flag sets can safely hold NaN, so they only have `PartialEq` and `PartialOrd`;
+/// `total_cmp` is available through `Deref`.
+///
+/// ```compile_fail
+/// use fast_floats::{flags::Algebraic, Fast};
+///
+/// let _ = Fast::<f64, Algebraic>::algebraic(1.).cmp(&Fast::algebraic(2.));
+/// ```
+impl<F: FastFloat> Eq for Fast<F> {}
+
+/// See [`Eq`](#impl-Eq-for-Fast<F>) for the contract. `-0.0` and `0.0` are equal.
+impl<F: FastFloat> Ord for Fast<F> {
+    #[inline(always)]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    fn cmp(&self, other: &Self) -> Ordering {
+        #[cfg(feature = "sanitize")]
+        {
+            sanitize::<_, AllFast>("cmp", "left operand", self.0);
+            sanitize::<_, AllFast>("cmp", "right operand", other.0);
+        }
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
     }
 }
 
-/// This is actually a bad idea, but is required for my use cases.
-/// Creating a Fast float should be unsafe - as fast floats use `core_intrinsics`.
-impl<F> From<F> for Fast<F> {
+/// Consistent with [`Eq`](#impl-Eq-for-Fast<F>): `-0.0` hashes like `0.0`.
+impl<F: FastFloat> Hash for Fast<F> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let x = if self.0 == F::ZERO { F::ZERO } else { self.0 };
+        x.hash_bits().hash(state)
+    }
+}
+
+macro_rules! impl_deref {
+    ([$($c:tt)?]) => {
+        impl<F, Flags> $($c)? Deref for Fast<F, Flags> {
+            type Target = F;
+
+            #[inline(always)]
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+    };
+}
+
+with_constness!(impl_deref! {});
+
+impl<F, Flags> DerefMut for Fast<F, Flags> {
     #[inline(always)]
-    fn from(f: F) -> Self {
-        Self(f)
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
     }
 }
 
+macro_rules! impl_from {
+    ([$($c:tt)?]) => {
+        /// This is actually a bad idea, but is required for my use cases.
+        /// Creating a Fast float should be unsafe - as fast floats use `core_intrinsics`.
+        impl<F, Flags> $($c)? From<F> for Fast<F, Flags> {
+            #[inline(always)]
+            fn from(f: F) -> Self {
+                Self(f, PhantomData)
+            }
+        }
+    };
+}
+
+with_constness!(impl_from! {});
+
 /// “fast-math” wrapper for `f64`
 pub type FF64 = Fast<f64>;
 /// “fast-math” wrapper for `f32`
 pub type FF32 = Fast<f32>;
+/// “fast-math” wrapper for `f16`
+#[cfg(all(nightly, feature = "f16"))]
+pub type FF16 = Fast<f16>;
+/// “fast-math” wrapper for `f128`
+#[cfg(all(nightly, feature = "f128"))]
+pub type FF128 = Fast<f128>;
+
+/// Wrapper using only the algebraic fast-math flags, see [`flags::Algebraic`].
+///
+/// The algebraic operations allow reassociation and contraction, which is what lets
+/// reductions vectorize, but have no undefined behaviour for NaN or infinite values.
+/// Because of that, values can be created without `unsafe` using [`Fast::algebraic`].
+pub type Algebraic<F> = Fast<F, flags::Algebraic>;
 
 impl<F> Fast<F> {
     /// Create a new fast value
@@ -83,109 +369,549 @@ impl<F> Fast<F> {
     /// Be wary of operations creating invalid values in `Fast` which they could potentially do
     /// depending on the operation.
     pub const unsafe fn new(value: F) -> Self {
-        Fast(value)
+        Fast(value, PhantomData)
     }
 }
 
-macro_rules! impl_op {
-    ($($name:ident, $method:ident, $intrins:ident;)*) => {
+impl<F> Algebraic<F> {
+    /// Create a new algebraic value
+    ///
+    /// This is safe, since the algebraic operations are defined for all values.
+    pub const fn algebraic(value: F) -> Self {
+        Fast(value, PhantomData)
+    }
+}
+
+impl<F, Flags> Fast<F, Flags> {
+    /// Create a new fast value using the fast-math flag set `Flags`
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Fast::new`], the value must be valid for the operations `Flags` enables.
+    pub const unsafe fn with_flags(value: F) -> Self {
+        Fast(value, PhantomData)
+    }
+}
+
+macro_rules! impl_consts {
+    ($($(#[$attr:meta])* $name:ident;)*) => {
         $(
-        // Fast<F> + F
-        impl $name<f64> for Fast<f64> {
-            type Output = Self;
+        $(#[$attr])*
+        pub const $name: Self = Fast(F::$name, PhantomData);
+        )*
+    };
+}
+
+/// Constants
+///
+/// These are finite, so they are valid for every flag set and need no `unsafe`.
+impl<F: FastFloat, Flags> Fast<F, Flags> {
+    impl_consts! {
+        /// Zero
+        ZERO;
+        /// One
+        ONE;
+        /// Machine epsilon, the difference between `1.0` and the next larger representable number
+        EPSILON;
+        /// Smallest positive normal value
+        MIN_POSITIVE;
+        /// Largest finite value
+        MAX;
+        /// Smallest finite value
+        MIN;
+        /// Archimedes' constant (π)
+        PI;
+        /// The full circle constant (τ = 2π)
+        TAU;
+        /// Euler's number (e)
+        E;
+        /// π/2
+        FRAC_PI_2;
+        /// π/3
+        FRAC_PI_3;
+        /// π/4
+        FRAC_PI_4;
+        /// π/6
+        FRAC_PI_6;
+        /// π/8
+        FRAC_PI_8;
+        /// 1/π
+        FRAC_1_PI;
+        /// 2/π
+        FRAC_2_PI;
+        /// 2/sqrt(π)
+        FRAC_2_SQRT_PI;
+        /// sqrt(2)
+        SQRT_2;
+        /// 1/sqrt(2)
+        FRAC_1_SQRT_2;
+        /// ln(2)
+        LN_2;
+        /// ln(10)
+        LN_10;
+        /// log<sub>2</sub>(e)
+        LOG2_E;
+        /// log<sub>2</sub>(10)
+        LOG2_10;
+        /// log<sub>10</sub>(e)
+        LOG10_E;
+        /// log<sub>10</sub>(2)
+        LOG10_2;
+    }
+}
+
+macro_rules! impl_default {
+    ([$($c:tt)?]) => {
+        /// Zero
+        impl<F: FastFloat, Flags> $($c)? Default for Fast<F, Flags> {
             #[inline(always)]
-            fn $method(self, rhs: f64) -> Self::Output {
-                unsafe {
-                    Fast($intrins(self.0, rhs))
-                }
+            fn default() -> Self {
+                Self::ZERO
             }
         }
+    };
+}
 
-        impl $name<f32> for Fast<f32> {
-            type Output = Self;
-            #[inline(always)]
-            fn $method(self, rhs: f32) -> Self::Output {
-                unsafe {
-                    Fast($intrins(self.0, rhs))
+with_constness!(impl_default! {});
+
+macro_rules! impl_try_new {
+    ([$($c:tt)?]) => {
+        // Inherent impls can not have `[const]` bounds, so they are on the methods
+        impl<F: FastFloat, Flags> Fast<F, Flags> {
+            /// Create a new fast value, if it is neither NaN nor infinite
+            ///
+            /// Note that this can not be offered as `TryFrom<F>`, since that is already implied
+            /// by the unconditional `From<F>`. On nightly this is `const`.
+            pub $($c)? fn try_new(value: F) -> Result<Self, InvalidFloat>
+            where
+                F: $([$c])? FastFloat,
+            {
+                if value.is_nan() {
+                    Err(InvalidFloat::Nan)
+                } else if value.is_infinite() {
+                    Err(InvalidFloat::Infinite)
+                } else {
+                    Ok(Fast(value, PhantomData))
                 }
             }
-        }
 
-        // F + Fast<F>
-        impl $name<Fast<f64>> for f64 {
-            type Output = Fast<f64>;
-            #[inline(always)]
-            fn $method(self, rhs: Fast<f64>) -> Self::Output {
-                Fast(self).$method(rhs.0)
+            /// Check that every element of `xs` could be used to create a fast value with
+            /// [`Fast::try_new`]
+            ///
+            /// Returns the index of the first element that can not. On nightly this is `const`.
+            pub $($c)? fn check_slice(xs: &[F]) -> Result<(), InvalidElement>
+            where
+                F: $([$c])? FastFloat,
+            {
+                let mut index = 0;
+                while index < xs.len() {
+                    if let Err(error) = Self::try_new(xs[index]) {
+                        return Err(InvalidElement { index, error });
+                    }
+                    index += 1;
+                }
+                Ok(())
             }
         }
+    };
+}
 
-        impl $name<Fast<f32>> for f32 {
-            type Output = Fast<f32>;
-            #[inline(always)]
-            fn $method(self, rhs: Fast<f32>) -> Self::Output {
-                Fast(self).$method(rhs.0)
-            }
+with_constness!(impl_try_new! {});
+
+/// Zero-copy conversions
+///
+/// These rely on `Fast` being `#[repr(transparent)]`.
+impl<F, Flags> Fast<F, Flags> {
+    /// View a slice of floats as a slice of fast values
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Fast::new`], for every element of the slice.
+    #[inline(always)]
+    pub unsafe fn from_slice(xs: &[F]) -> &[Self] {
+        unsafe { &*(xs as *const [F] as *const [Self]) }
+    }
+
+    /// View a mutable slice of floats as a mutable slice of fast values
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Fast::new`], for every element of the slice.
+    #[inline(always)]
+    pub unsafe fn from_slice_mut(xs: &mut [F]) -> &mut [Self] {
+        unsafe { &mut *(xs as *mut [F] as *mut [Self]) }
+    }
+
+    /// View a slice of fast values as a slice of floats
+    #[inline(always)]
+    pub fn slice_as_inner(xs: &[Self]) -> &[F] {
+        unsafe { &*(xs as *const [Self] as *const [F]) }
+    }
+
+    /// View a mutable slice of fast values as a mutable slice of floats
+    #[inline(always)]
+    pub fn slice_as_inner_mut(xs: &mut [Self]) -> &mut [F] {
+        unsafe { &mut *(xs as *mut [Self] as *mut [F]) }
+    }
+
+    /// Convert a vector of floats into a vector of fast values, without copying
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Fast::new`], for every element of the vector.
+    #[cfg(feature = "alloc")]
+    pub unsafe fn from_vec(xs: Vec<F>) -> Vec<Self> {
+        let mut xs = ManuallyDrop::new(xs);
+        unsafe { Vec::from_raw_parts(xs.as_mut_ptr() as *mut Self, xs.len(), xs.capacity()) }
+    }
+
+    /// Convert a vector of fast values into a vector of floats, without copying
+    #[cfg(feature = "alloc")]
+    pub fn vec_into_inner(xs: Vec<Self>) -> Vec<F> {
+        let mut xs = ManuallyDrop::new(xs);
+        unsafe { Vec::from_raw_parts(xs.as_mut_ptr() as *mut F, xs.len(), xs.capacity()) }
+    }
+
+    /// Convert a boxed slice of floats into a boxed slice of fast values, without copying
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Fast::new`], for every element of the slice.
+    #[cfg(feature = "alloc")]
+    pub unsafe fn from_boxed_slice(xs: Box<[F]>) -> Box<[Self]> {
+        unsafe { Box::from_raw(Box::into_raw(xs) as *mut [Self]) }
+    }
+
+    /// Convert a boxed slice of fast values into a boxed slice of floats, without copying
+    #[cfg(feature = "alloc")]
+    pub fn boxed_slice_into_inner(xs: Box<[Self]>) -> Box<[F]> {
+        unsafe { Box::from_raw(Box::into_raw(xs) as *mut [F]) }
+    }
+}
+
+/// Panic if `value` breaks the assumptions of the flag set, see the `sanitize` feature.
+///
+/// Nothing is checked during const evaluation, which uses the strict operations.
+#[cfg(all(feature = "sanitize", nightly))]
+#[track_caller]
+const fn sanitize<F: FastFloat, Flags: FlagSet>(op: &'static str, what: &'static str, value: F) {
+    const fn unchecked<F: Copy>(_: &str, _: &str, _: F) {}
+    std::intrinsics::const_eval_select((op, what, value), unchecked, check::<F, Flags>)
+}
+
+#[cfg(all(feature = "sanitize", not(nightly)))]
+use check as sanitize;
+
+#[cfg(feature = "sanitize")]
+#[track_caller]
+fn check<F: FastFloat, Flags: FlagSet>(op: &str, what: &str, value: F) {
+    if Flags::ASSUMES_FINITE {
+        if let Err(error) = Fast::<F, Flags>::try_new(value) {
+            panic!("fast-floats: {} of `{}` is invalid: {}", what, op, error);
         }
+    }
+}
 
-        // Fast<F> + Fast<F>
-        impl $name for Fast<f64> {
+macro_rules! impl_op {
+    ([$($c:tt)?]) => {};
+    ([$($c:tt)?] $name:ident, $method:ident; $($rest:tt)*) => {
+        // Fast<F> + F
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? $name<F> for Fast<F, Flags> {
             type Output = Self;
             #[inline(always)]
-            fn $method(self, rhs: Self) -> Self::Output {
-                self.$method(rhs.0)
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: F) -> Self::Output {
+                #[cfg(feature = "sanitize")]
+                {
+                    sanitize::<_, Flags>(stringify!($method), "left operand", self.0);
+                    sanitize::<_, Flags>(stringify!($method), "right operand", rhs);
+                }
+                let result = unsafe { Flags::$method(self.0, rhs) };
+                #[cfg(feature = "sanitize")]
+                sanitize::<_, Flags>(stringify!($method), "result", result);
+                Fast(result, PhantomData)
             }
         }
 
-        impl $name for Fast<f32> {
+        // Fast<F> + Fast<F>
+        impl<F: $([$c])? FastFloat, Flags: $([$c])? FlagSet> $($c)? $name for Fast<F, Flags> {
             type Output = Self;
             #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
             fn $method(self, rhs: Self) -> Self::Output {
                 self.$method(rhs.0)
             }
         }
+
+        // F + Fast<F>, and the same with references to either operand
+        impl_rev_op!([$($c)?] $name, $method; Fast);
+        impl_ref_op!([$($c)?] $name, $method; [F: $([$c])? FastFloat, Flags: $([$c])? FlagSet] Fast<F, Flags>, F);
+        impl_ref_op!([$($c)?] $name, $method; [F: $([$c])? FastFloat, Flags: $([$c])? FlagSet] Fast<F, Flags>, Fast<F, Flags>);
+
+        impl_op!([$($c)?] $($rest)*);
+    };
+}
+
+with_constness!(impl_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+});
+
+with_constness!(impl_assignop! {
+    Fast;
+    AddAssign, add_assign, Add, add;
+    SubAssign, sub_assign, Sub, sub;
+    MulAssign, mul_assign, Mul, mul;
+    DivAssign, div_assign, Div, div;
+    RemAssign, rem_assign, Rem, rem;
+});
+
+/// Multiply-add
+impl<F: FastFloat, Flags: FlagSet> Fast<F, Flags> {
+    /// `self * a + b`, fused into one operation if the flag set allows contraction and the
+    /// target has a fused multiply-add instruction
+    ///
+    /// With [`Strict`](flags::Strict) it is always fused, like `f64::mul_add`. On stable it is
+    /// never fused.
+    #[inline(always)]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        #[cfg(feature = "sanitize")]
+        {
+            sanitize::<_, Flags>("mul_add", "left operand", self.0);
+            sanitize::<_, Flags>("mul_add", "multiplier", a.0);
+            sanitize::<_, Flags>("mul_add", "addend", b.0);
+        }
+        let result = Flags::mul_add(self.0, a.0, b.0);
+        #[cfg(feature = "sanitize")]
+        sanitize::<_, Flags>("mul_add", "result", result);
+        Fast(result, PhantomData)
+    }
+
+    /// `self * a - b`, see [`Fast::mul_add`]
+    #[inline(always)]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    pub fn mul_sub(self, a: Self, b: Self) -> Self {
+        self.mul_add(a, -b)
+    }
+
+    /// Evaluate the polynomial with coefficients `coeffs` at `self`, using Horner's method
+    ///
+    /// The coefficients are in order of increasing degree, so `[c0, c1, c2]` is
+    /// `c0 + c1 * x + c2 * x²`. An empty polynomial is zero.
+    #[inline]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    pub fn poly_eval(self, coeffs: &[Self]) -> Self {
+        let Some((&last, rest)) = coeffs.split_last() else {
+            return Fast(F::ZERO, PhantomData);
+        };
+        // a loop rather than `fold`, so that `track_caller` reaches `mul_add`
+        let mut acc = last;
+        for &c in rest.iter().rev() {
+            acc = acc.mul_add(self, c);
+        }
+        acc
+    }
+}
+
+#[cfg(nightly)]
+macro_rules! impl_math {
+    ($($(#[$attr:meta])* $name:ident;)*) => {
+        $(
+        $(#[$attr])*
+        #[inline(always)]
+        #[cfg_attr(feature = "sanitize", track_caller)]
+        pub fn $name(self) -> Self {
+            let result = F::$name(self.0);
+            #[cfg(feature = "sanitize")]
+            sanitize::<_, Flags>(stringify!($name), "result", result);
+            Fast(result, PhantomData)
+        }
         )*
+    };
+}
+
+/// Math functions
+///
+/// These use the float intrinsics of `core`, so they work in `no_std` builds and return
+/// `Fast` values. The intrinsics take no fast-math flags: the results are the same for
+/// every flag set. Nightly only.
+#[cfg(nightly)]
+impl<F: FastFloat, Flags: FlagSet> Fast<F, Flags> {
+    impl_math! {
+        /// The square root
+        sqrt;
+        /// `e^self`
+        exp;
+        /// `2^self`
+        exp2;
+        /// The natural logarithm
+        ln;
+        /// The base 2 logarithm
+        log2;
+        /// The base 10 logarithm
+        log10;
+        /// The sine, in radians
+        sin;
+        /// The cosine, in radians
+        cos;
+        /// The largest integer less than or equal to `self`
+        floor;
+        /// The smallest integer greater than or equal to `self`
+        ceil;
+        /// The nearest integer, rounding half-way cases away from zero
+        round;
+        /// The integer part of `self`
+        trunc;
+    }
+
+    /// `self` to the power `n`
+    #[inline(always)]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    pub fn powf(self, n: Self) -> Self {
+        let result = self.0.powf(n.0);
+        #[cfg(feature = "sanitize")]
+        sanitize::<_, Flags>("powf", "result", result);
+        Fast(result, PhantomData)
+    }
 
+    /// `self` to the integer power `n`
+    #[inline(always)]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    pub fn powi(self, n: i32) -> Self {
+        let result = self.0.powi(n);
+        #[cfg(feature = "sanitize")]
+        sanitize::<_, Flags>("powi", "result", result);
+        Fast(result, PhantomData)
     }
 }
 
-macro_rules! impl_assignop {
-    ($($name:ident, $method:ident, $optrt:ident, $opmth:ident;)*) => {
+macro_rules! impl_iter {
+    ($($name:ident, $method:ident, $init:ident, $op:ident;)*) => {
         $(
-        impl<F, Rhs> $name<Rhs> for Fast<F>
-            where Self: $optrt<Rhs, Output=Self> + Copy,
-        {
-            #[inline(always)]
-            fn $method(&mut self, rhs: Rhs) {
-                *self = (*self).$opmth(rhs)
+        impl<F: FastFloat, Flags: FlagSet> $name for Fast<F, Flags> {
+            #[inline]
+            fn $method<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Fast(F::$init, PhantomData), |acc, x| acc.$op(x))
+            }
+        }
+
+        impl<'a, F: FastFloat, Flags: FlagSet> $name<&'a Self> for Fast<F, Flags> {
+            #[inline]
+            fn $method<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Fast(F::$init, PhantomData), |acc, x| acc.$op(x))
+            }
+        }
+
+        impl<F: FastFloat, Flags: FlagSet> $name<F> for Fast<F, Flags> {
+            #[inline]
+            fn $method<I: Iterator<Item = F>>(iter: I) -> Self {
+                iter.fold(Fast(F::$init, PhantomData), |acc, x| acc.$op(x))
             }
         }
-        )*
 
+        impl<'a, F: FastFloat, Flags: FlagSet> $name<&'a F> for Fast<F, Flags> {
+            #[inline]
+            fn $method<I: Iterator<Item = &'a F>>(iter: I) -> Self {
+                iter.fold(Fast(F::$init, PhantomData), |acc, x| acc.$op(x))
+            }
+        }
+        )*
     }
 }
 
-impl_op! {
-    Add, add, fadd_fast;
-    Sub, sub, fsub_fast;
-    Mul, mul, fmul_fast;
-    Div, div, fdiv_fast;
-    Rem, rem, frem_fast;
+impl_iter! {
+    Sum, sum, ZERO, add;
+    Product, product, ONE, mul;
 }
 
-impl_assignop! {
-    AddAssign, add_assign, Add, add;
-    SubAssign, sub_assign, Sub, sub;
-    MulAssign, mul_assign, Mul, mul;
-    DivAssign, div_assign, Div, div;
-    RemAssign, rem_assign, Rem, rem;
+macro_rules! impl_neg {
+    ([$($c:tt)?]) => {
+        impl<F: $([$c])? FastFloat, Flags> $($c)? Neg for Fast<F, Flags> {
+            type Output = Self;
+            #[inline(always)]
+            fn neg(self) -> Self::Output {
+                Fast(-self.0, PhantomData)
+            }
+        }
+    };
+}
+
+with_constness!(impl_neg! {});
+
+/// Sign utilities
+///
+/// These only manipulate the sign bit or use the fast operations, so unlike the float methods
+/// they reach through `Deref` they have no special cases for NaN.
+impl<F: FastFloat, Flags: FlagSet> Fast<F, Flags> {
+    /// The absolute value
+    #[inline(always)]
+    pub fn abs(self) -> Self {
+        Fast(self.0.abs(), PhantomData)
+    }
+
+    /// `1` with the sign of `self`, so `-0.0` gives `-1.0`
+    #[inline(always)]
+    pub fn signum(self) -> Self {
+        Fast(F::ONE.copysign(self.0), PhantomData)
+    }
+
+    /// The magnitude of `self` with the sign of `sign`
+    #[inline(always)]
+    pub fn copysign(self, sign: Self) -> Self {
+        Fast(self.0.copysign(sign.0), PhantomData)
+    }
+
+    /// The reciprocal, `1 / self`, using the fast division
+    #[inline(always)]
+    #[cfg_attr(feature = "sanitize", track_caller)]
+    pub fn recip(self) -> Self {
+        Fast(F::ONE, PhantomData) / self
+    }
+
+    /// The smaller of `self` and `other`
+    ///
+    /// A plain comparison, so it compiles to a single instruction on most targets. If the
+    /// values compare equal (such as `-0.0` and `0.0`) or either is NaN, it returns `other`.
+    #[inline(always)]
+    pub fn min(self, other: Self) -> Self {
+        if self.0 < other.0 {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// The larger of `self` and `other`
+    ///
+    /// Like [`Fast::min`], it returns `other` if the values compare equal or either is NaN.
+    #[inline(always)]
+    pub fn max(self, other: Self) -> Self {
+        if self.0 > other.0 {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Restrict the value to the interval `[min, max]`
+    ///
+    /// Unlike `f64::clamp` this does not panic if `min > max`; the result is then unspecified.
+    #[inline(always)]
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        let x = if self.0 < min.0 { min.0 } else { self.0 };
+        Fast(if x > max.0 { max.0 } else { x }, PhantomData)
+    }
 }
 
 use std::fmt;
 macro_rules! impl_format {
     ($($name:ident)+) => {
         $(
-        impl<F: fmt::$name> fmt::$name for Fast<F> {
+        impl<F: fmt::$name, Flags> fmt::$name for Fast<F, Flags> {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
                 self.0.fmt(f)
             }
@@ -199,26 +925,98 @@ impl_format!(Debug Display LowerExp UpperExp);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use flags::Strict;
+
+    fn fast<Flags>(x: f64) -> Fast<f64, Flags> {
+        Fast::from(x)
+    }
 
     macro_rules! test_op {
-        ($($op:tt)+) => {
+        ($flags:ty: $($op:tt)+) => {
             $(
-                assert_eq!(Fast(2.) $op Fast(1.), Fast(2. $op 1.));
+                assert_eq!(fast::<$flags>(2.) $op fast(1.), fast(2. $op 1.));
             )+
         }
     }
 
     #[test]
     fn each_op() {
-        test_op!(+ - * / %);
+        test_op!(AllFast: + - * / %);
+    }
+
+    macro_rules! test_ref_op {
+        ($($op:tt)+) => {
+            $(
+                let (x, y) = (fast::<AllFast>(2.), fast::<AllFast>(1.));
+                let z = fast(2. $op 1.);
+                assert_eq!(&x $op y, z);
+                assert_eq!(x $op &y, z);
+                assert_eq!(&x $op &y, z);
+                assert_eq!(&x $op 1., z);
+                assert_eq!(x $op &1., z);
+                assert_eq!(&x $op &1., z);
+                assert_eq!(&2. $op y, z);
+                assert_eq!(2. $op &y, z);
+                assert_eq!(&2. $op &y, z);
+            )+
+        }
+    }
+
+    #[test]
+    fn each_ref_op() {
+        test_ref_op!(+ - * / %);
+    }
+
+    #[test]
+    fn sum_product() {
+        let xs = [1., 2., 3., 4.];
+        let fs = xs.map(fast::<AllFast>);
+        assert_eq!(fs.iter().sum::<FF64>(), fast(10.));
+        assert_eq!(fs.into_iter().product::<FF64>(), fast(24.));
+        assert_eq!(xs.iter().copied().map(FF64::from).sum::<FF64>(), fast(10.));
+        assert_eq!(xs.iter().sum::<FF64>(), fast(10.));
+        assert_eq!(xs.into_iter().product::<Algebraic<f64>>(), fast(24.));
+        assert_eq!([0f64; 0].iter().sum::<FF64>(), fast(0.));
+        assert_eq!([0f64; 0].iter().product::<FF64>(), fast(1.));
+    }
+
+    #[test]
+    fn each_op_flags() {
+        test_op!(flags::Algebraic: + - * / %);
+        test_op!(Strict: + - * / %);
+    }
+
+    macro_rules! test_unary {
+        ($($method:ident($x:literal $(, $arg:literal)*) is $z:literal;)+) => {
+            $(
+                assert_eq!(fast::<AllFast>($x).$method($(fast($arg)),*), fast($z));
+            )+
+        }
+    }
+
+    #[test]
+    fn each_unary() {
+        assert_eq!(-fast::<AllFast>(2.), fast(-2.));
+        test_unary!(
+            abs(-2.) is 2.;
+            signum(-2.) is -1.;
+            signum(0.) is 1.;
+            signum(-0.) is -1.;
+            copysign(2., -1.) is -2.;
+            copysign(-2., 0.) is 2.;
+            recip(4.) is 0.25;
+            clamp(-1., 0., 1.) is 0.;
+            clamp(0.5, 0., 1.) is 0.5;
+            clamp(2., 0., 1.) is 1.;
+        );
     }
 
     macro_rules! assign_op {
         ($($x:literal $op:tt $y:literal is $z:literal ;)+) => {
             $(
-                let mut x = Fast($x);
-                x $op Fast($y);
-                assert_eq!(x, Fast($z));
+                let mut x = fast::<AllFast>($x);
+                x $op fast($y);
+                assert_eq!(x, fast($z));
             )+
         }
     }
@@ -232,12 +1030,412 @@ mod tests {
             2. /= 2. is 1.;
             5. %= 2. is 1.;
         );
+
+        let mut x = fast::<AllFast>(1.);
+        x += &fast(2.);
+        x *= &2.;
+        assert_eq!(x, fast(6.));
+    }
+
+    #[test]
+    fn algebraic() {
+        let mut x = Fast::algebraic(1.);
+        x += Fast::algebraic(2.);
+        x *= 2.;
+        assert_eq!(x, Algebraic::algebraic(6.));
+        assert_eq!(*(x / f64::INFINITY), 0.);
+    }
+
+    #[test]
+    fn checked() {
+        assert_eq!(FF64::try_new(1.), Ok(fast(1.)));
+        assert_eq!(FF64::try_new(f64::NAN), Err(InvalidFloat::Nan));
+        assert_eq!(
+            FF32::try_new(f32::NEG_INFINITY),
+            Err(InvalidFloat::Infinite)
+        );
+        assert_eq!(FF64::check_slice(&[0., 1., f64::MAX]), Ok(()));
+        assert_eq!(
+            FF64::check_slice(&[0., f64::INFINITY, f64::NAN]),
+            Err(InvalidElement {
+                index: 1,
+                error: InvalidFloat::Infinite
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "right operand of `add` is invalid: value is NaN")]
+    fn sanitize_operand() {
+        let _ = fast::<AllFast>(1.) + f64::NAN;
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "result of `mul` is invalid: value is infinite")]
+    fn sanitize_result() {
+        let mut x = fast::<AllFast>(f64::MAX);
+        x *= 2.;
+    }
+
+    #[test]
+    #[cfg(all(nightly, feature = "sanitize"))]
+    #[should_panic(expected = "result of `sqrt` is invalid: value is NaN")]
+    fn sanitize_sqrt() {
+        let _ = FF64::from(-1.).sqrt();
+    }
+
+    #[test]
+    #[cfg(all(nightly, feature = "sanitize"))]
+    #[should_panic(expected = "result of `ln` is invalid: value is infinite")]
+    fn sanitize_ln() {
+        let _ = FF64::from(0.).ln();
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "result of `mul_add` is invalid: value is infinite")]
+    fn sanitize_mul_add() {
+        let _ = FF64::from(1e300).mul_add(FF64::from(1e300), FF64::ZERO);
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "result of `mul_add` is invalid: value is infinite")]
+    fn sanitize_poly_eval() {
+        let _ = FF64::MAX.poly_eval(&[FF64::ZERO, FF64::MAX]);
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    fn sanitize_algebraic() {
+        let _ = Fast::algebraic(1.) + f64::NAN;
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "left operand of `cmp` is invalid: value is NaN")]
+    fn sanitize_cmp() {
+        let _ = fast::<AllFast>(f64::NAN).cmp(&fast(1.));
+    }
+
+    #[test]
+    #[cfg(all(feature = "strict", not(feature = "sanitize")))]
+    fn strict() {
+        assert!((fast::<AllFast>(1.) + f64::NAN).is_nan());
+        assert_eq!(*(fast::<AllFast>(1.) / 0.), f64::INFINITY);
+    }
+
+    fn kernel<F: FastFloat>(x: Fast<F>, y: Fast<F>) -> Fast<F> {
+        let mut z = x * y + x;
+        z -= y;
+        z / x
+    }
+
+    #[test]
+    fn generic() {
+        assert_eq!(kernel(fast(2.), fast(3.)), fast(2.5));
+        assert_eq!(kernel(FF32::from(2.), FF32::from(3.)), FF32::from(2.5));
+    }
+
+    #[test]
+    fn slices() {
+        let mut xs = [1., 2., 3.];
+        let fs = unsafe { FF64::from_slice(&xs) };
+        assert_eq!(fs.iter().sum::<FF64>(), fast(6.));
+        assert_eq!(FF64::slice_as_inner(fs), &[1., 2., 3.]);
+
+        let fs = unsafe { FF64::from_slice_mut(&mut xs) };
+        fs[0] += 1.;
+        FF64::slice_as_inner_mut(fs)[1] = 0.;
+        assert_eq!(xs, [2., 0., 3.]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn owned_slices() {
+        use alloc::vec;
+
+        let xs = vec![1., 2., 3.];
+        let ptr = xs.as_ptr();
+        let fs = unsafe { FF64::from_vec(xs) };
+        assert_eq!(fs, [fast(1.), fast(2.), fast(3.)]);
+        let xs = FF64::vec_into_inner(fs);
+        assert_eq!(xs.as_ptr(), ptr);
+
+        let fs = unsafe { FF64::from_boxed_slice(xs.into_boxed_slice()) };
+        assert_eq!(fs.iter().sum::<FF64>(), fast(6.));
+        assert_eq!(*FF64::boxed_slice_into_inner(fs), [1., 2., 3.]);
+    }
+
+    /// Hashes the written bytes, since `std` is not available
+    #[derive(Default)]
+    struct BytesHasher(u64);
+
+    impl Hasher for BytesHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = self.0.rotate_left(8) ^ byte as u64;
+            }
+        }
+    }
+
+    fn hash<T: Hash>(value: T) -> u64 {
+        let mut hasher = BytesHasher::default();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn total_order() {
+        let mut xs = [3., -1., 0., 2.5, -0., 1e10].map(FF64::from);
+        xs.sort_unstable();
+        assert_eq!(xs, [-1., 0., 0., 2.5, 3., 1e10].map(FF64::from));
+        assert_eq!(xs.iter().max(), Some(&FF64::from(1e10)));
+        assert_eq!(FF64::from(-0.).cmp(&FF64::from(0.)), Ordering::Equal);
+
+        assert_eq!(hash(FF64::from(-0.)), hash(FF64::from(0.)));
+        assert_eq!(hash(FF32::from(1.5)), hash(FF32::from(1.5)));
+        assert_ne!(hash(FF32::from(1.5)), hash(FF32::from(-1.5)));
+
+        let (a, b) = (FF32::from(-2.), FF32::from(3.));
+        assert_eq!((a.min(b), a.max(b)), (a, b));
+        assert_eq!((b.min(a), b.max(a)), (a, b));
+    }
+
+    #[test]
+    fn nan_order() {
+        let nan = Fast::<f64, flags::Algebraic>::algebraic(0.) / 0.;
+        assert!(nan.is_nan());
+        assert_ne!(nan, nan);
+        assert_eq!(nan.partial_cmp(&nan), None);
+        assert_eq!(nan.partial_cmp(&Fast::algebraic(1.)), None);
+        let nan = Algebraic::algebraic(f64::NAN);
+        assert_eq!(nan.total_cmp(&1.), Ordering::Greater);
+
+        let nan = Fast::<f64, Strict>::from(f64::NAN);
+        let one = Fast::from(1.);
+        assert_eq!((*nan.min(one), *nan.max(one)), (1., 1.));
+        assert!(one.min(nan).is_nan() && one.max(nan).is_nan());
+        let (zero, neg_zero) = (Fast::<f64, Strict>::from(0.), Fast::from(-0.));
+        assert!(zero.min(neg_zero).is_sign_negative());
+        assert!(neg_zero.max(zero).is_sign_positive());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn btree_map() {
+        let mut map = alloc::collections::BTreeMap::new();
+        for (i, x) in [0.5, -2., 7.].into_iter().enumerate() {
+            map.insert(FF64::from(x), i);
+        }
+        assert_eq!(map.keys().next(), Some(&FF64::from(-2.)));
+        assert_eq!(map[&FF64::from(7.)], 2);
+    }
+
+    /// Distance in units in the last place
+    fn ulps(a: f64, b: f64) -> u64 {
+        let key = |x: f64| {
+            let bits = x.to_bits() as i64;
+            if bits < 0 {
+                i64::MIN - bits
+            } else {
+                bits
+            }
+        };
+        key(a).abs_diff(key(b))
+    }
+
+    #[test]
+    fn mul_add() {
+        let (x, a, b) = (0.1f64, 0.7, 0.3);
+        let fused = x.mul_add(a, b);
+        assert!(ulps(*fast::<AllFast>(x).mul_add(fast(a), fast(b)), fused) <= 1);
+        assert!(ulps(*fast::<AllFast>(x).mul_sub(fast(a), fast(-b)), fused) <= 1);
+        #[cfg(nightly)]
+        assert_eq!(*fast::<Strict>(x).mul_add(fast(a), fast(b)), fused);
+    }
+
+    #[test]
+    fn poly_eval() {
+        // Taylor polynomial of exp
+        let coeffs = [1., 1., 1. / 2., 1. / 6., 1. / 24., 1. / 120.];
+        let strict = |x: f64| coeffs.iter().rev().fold(0., |acc, c| acc * x + c);
+        let fast_coeffs = coeffs.map(fast::<AllFast>);
+        for i in 0..=100 {
+            let x = i as f64 / 100.;
+            assert!(ulps(*fast(x).poly_eval(&fast_coeffs), strict(x)) <= 4);
+        }
+        assert_eq!(fast(2.).poly_eval(&fast_coeffs[..3]), fast(5.));
+        assert_eq!(fast::<AllFast>(2.).poly_eval(&[]), fast(0.));
+    }
+
+    #[test]
+    fn consts() {
+        const TABLE: [FF64; 3] = [FF64::ZERO, FF64::ONE, FF64::PI];
+        assert_eq!(*TABLE[2], std::f64::consts::PI);
+        assert_eq!(*FF32::EPSILON, f32::EPSILON);
+        assert_eq!(*Fast::<f32, Strict>::MIN_POSITIVE, f32::MIN_POSITIVE);
+        assert_eq!(*Algebraic::<f64>::LOG10_2, std::f64::consts::LOG10_2);
+        assert_eq!(FF64::default(), FF64::ZERO);
+        #[cfg(nightly)]
+        {
+            const DEFAULT: FF32 = FF32::default();
+            assert_eq!(DEFAULT, FF32::ZERO);
+        }
+
+        fn tau<F: FastFloat>() -> Fast<F> {
+            Fast::<F>::PI + Fast::<F>::PI
+        }
+        assert_eq!(tau::<f64>(), FF64::TAU);
+        assert_eq!(tau::<f32>(), FF32::TAU);
+    }
+
+    #[cfg(nightly)]
+    #[test]
+    fn const_ops() {
+        // Taylor coefficients of exp, 1 / k!
+        const EXP: [FF64; 8] = {
+            let mut coeffs = [FF64::ONE; 8];
+            let mut k = 1;
+            while k < coeffs.len() {
+                coeffs[k] = coeffs[k - 1] / k as f64;
+                k += 1;
+            }
+            coeffs
+        };
+        let mut c = FF64::ONE;
+        for (k, &coeff) in EXP.iter().enumerate().skip(1) {
+            c /= k as f64;
+            assert_eq!(coeff, c);
+        }
+        let x = FF64::from(0.25);
+        assert!((*x.poly_eval(&EXP) - 0.25f64.exp()).abs() < 1e-9);
+
+        // Chebyshev polynomial T4 = 8x⁴ - 8x² + 1, from T(n+1) = 2x T(n) - T(n-1)
+        const T4: [Fast<f32, Strict>; 5] = {
+            let two = Fast::<f32, Strict>::ONE + 1.;
+            let mut t = [[Fast::<f32, Strict>::ZERO; 5]; 5];
+            t[0][0] = Fast::ONE;
+            t[1][1] = Fast::ONE;
+            let mut n = 2;
+            while n < 5 {
+                let mut i = 0;
+                while i <= n {
+                    t[n][i] = -t[n - 2][i];
+                    if i > 0 {
+                        t[n][i] += two * t[n - 1][i - 1];
+                    }
+                    i += 1;
+                }
+                n += 1;
+            }
+            t[4]
+        };
+        assert_eq!(T4, [1., 0., -8., 0., 8.].map(Fast::from));
+
+        const MIXED: Algebraic<f64> = 3. - Fast::from(1.) * 2. % Fast::algebraic(3.);
+        assert_eq!(MIXED, Fast::algebraic(1.));
+
+        const CHECKED: [Result<FF64, InvalidFloat>; 2] =
+            [FF64::try_new(1.5), FF64::try_new(f64::NAN)];
+        assert_eq!(CHECKED, [Ok(FF64::from(1.5)), Err(InvalidFloat::Nan)]);
+        const INFINITE: Result<(), InvalidElement> = FF32::check_slice(&[1., 2., f32::INFINITY]);
+        assert_eq!(
+            INFINITE,
+            Err(InvalidElement {
+                index: 2,
+                error: InvalidFloat::Infinite
+            })
+        );
     }
 
     #[test]
     fn deref() {
         let a = unsafe { FF32::new(2.) };
-        assert_eq!(a.sin(), 2f32.sin())
+        assert_eq!(a.to_degrees(), 2f32.to_degrees())
+    }
+
+    #[cfg(nightly)]
+    #[test]
+    fn math() {
+        let x = FF64::from(2.5);
+        assert_eq!(x.sqrt(), FF64::from(2.5f64.sqrt()));
+        assert_eq!(x.exp(), FF64::from(2.5f64.exp()));
+        assert_eq!(x.exp2(), FF64::from(2.5f64.exp2()));
+        assert_eq!(x.ln(), FF64::from(2.5f64.ln()));
+        assert_eq!(x.log2(), FF64::from(2.5f64.log2()));
+        assert_eq!(x.log10(), FF64::from(2.5f64.log10()));
+        assert_eq!(x.sin(), FF64::from(2.5f64.sin()));
+        assert_eq!(x.cos(), FF64::from(2.5f64.cos()));
+        assert_eq!(x.powf(x), FF64::from(2.5f64.powf(2.5)));
+        assert_eq!(x.powi(3), FF64::from(15.625));
+        assert_eq!(x.floor(), FF64::from(2.));
+        assert_eq!(x.ceil(), FF64::from(3.));
+        assert_eq!(x.round(), FF64::from(3.));
+        assert_eq!((-x).round(), FF64::from(-3.));
+        assert_eq!(x.trunc(), FF64::from(2.));
+        // chains stay `Fast`
+        let y: FF32 = FF32::from(4.).sqrt().ln().exp();
+        assert_eq!(y, FF32::from(2.));
+    }
+
+    #[cfg(all(nightly, feature = "f16"))]
+    #[test]
+    fn half() {
+        let (x, y) = (FF16::from(1.5), FF16::from(0.25));
+        assert_eq!(x + y, FF16::from(1.75));
+        assert_eq!(x - y, FF16::from(1.25));
+        assert_eq!(x * y, FF16::from(0.375));
+        assert_eq!(x / y, FF16::from(6.));
+        assert_eq!(x % y, FF16::ZERO);
+        assert_eq!(2. * x, FF16::from(3.));
+        assert_eq!(&x - 1., FF16::from(0.5));
+        assert_eq!(-x, FF16::from(-1.5));
+        let mut z = x;
+        z += y;
+        z *= 2.;
+        assert_eq!(z, FF16::from(3.5));
+        assert_eq!([x, y].iter().sum::<FF16>(), FF16::from(1.75));
+        assert_eq!(FF16::from(2.).mul_add(x, y), FF16::from(3.25));
+        assert_eq!(FF16::from(6.25).sqrt(), FF16::from(2.5));
+        let v = Fast::<[f16; 2]>::from([1.5, 0.25]);
+        assert_eq!(2. * v, Fast::from([3., 0.5]));
+        // 2049 is not representable: rounds to even
+        assert_eq!(FF16::from(2048.) + 1., FF16::from(2048.));
+        assert_eq!(*FF16::MAX, 65504.);
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            alloc::format!("{} {:?} {:e}", x, y, FF16::from(1024.)),
+            "1.5 0.25 1.024e3"
+        );
+    }
+
+    #[cfg(all(nightly, feature = "f128"))]
+    #[test]
+    fn quad() {
+        let (x, y) = (FF128::from(1.5), FF128::from(0.25));
+        assert_eq!(x + y, FF128::from(1.75));
+        assert_eq!(x - y, FF128::from(1.25));
+        assert_eq!(x * y, FF128::from(0.375));
+        assert_eq!(x / y, FF128::from(6.));
+        assert_eq!(x % y, FF128::ZERO);
+        assert_eq!(2. * x, FF128::from(3.));
+        assert_eq!(&x - 1., FF128::from(0.5));
+        let mut z = x;
+        z -= y;
+        z /= 0.5;
+        assert_eq!(z, FF128::from(2.5));
+        // 2^100 + 1 needs more than the 53 bits of f64
+        let big = FF128::from(2.).powi(100);
+        assert_eq!(*(big + 1. - big), 1.);
+        assert_eq!(FF128::ONE + FF128::EPSILON, FF128::from(1. + f128::EPSILON));
+        assert_eq!(FF128::ONE.to_bits(), 0x3fff << 112);
     }
 
     #[test]
@@ -0,0 +1,213 @@
+//! Reductions over slices.
+//!
+//! These use several independent `Fast` accumulators, so that the additions do not form one
+//! long dependency chain: the loop vectorizes and pipelines even with the [`Strict`] flags,
+//! and the result does not depend on the compiler reassociating a serial loop.
+//!
+//! All functions accept slices of plain floats (using the [`Algebraic`] flags, which are
+//! defined for every input) or of `Fast` values, see [`Element`].
+//!
+//! [`Algebraic`]: crate::flags::Algebraic
+//! [`Strict`]: crate::flags::Strict
+
+use crate::flags::{Algebraic, FlagSet};
+use crate::{Fast, FastFloat};
+
+/// Number of independent accumulators.
+pub(crate) const ACCUMULATORS: usize = 8;
+
+/// An element of a slice that can be reduced.
+///
+/// Implemented for `Fast`, and for the float types using the [`Algebraic`] flags. Plain floats
+/// have not been checked, so they can not use the flags that assume finite values.
+pub trait Element: Copy {
+    /// The float type
+    type Float: FastFloat;
+    /// The fast-math flags the reduction uses
+    type Flags: FlagSet;
+
+    /// Convert the element to a fast value
+    fn to_fast(self) -> Fast<Self::Float, Self::Flags>;
+}
+
+impl<F: FastFloat> Element for F {
+    type Float = F;
+    type Flags = Algebraic;
+
+    #[inline(always)]
+    fn to_fast(self) -> Fast<F, Algebraic> {
+        Fast::algebraic(self)
+    }
+}
+
+impl<F: FastFloat, Flags: FlagSet> Element for Fast<F, Flags> {
+    type Float = F;
+    type Flags = Flags;
+
+    #[inline(always)]
+    fn to_fast(self) -> Self {
+        self
+    }
+}
+
+type Output<T> = Fast<<T as Element>::Float, <T as Element>::Flags>;
+
+/// Fold `xs` into `ACCUMULATORS` accumulators with `f`, then combine them with `combine`.
+#[inline(always)]
+fn fold<T: Element>(
+    xs: &[T],
+    init: Output<T>,
+    f: impl Fn(Output<T>, Output<T>) -> Output<T>,
+    combine: impl Fn(Output<T>, Output<T>) -> Output<T>,
+) -> Output<T> {
+    let mut acc = [init; ACCUMULATORS];
+    let chunks = xs.chunks_exact(ACCUMULATORS);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        for (a, &x) in acc.iter_mut().zip(chunk) {
+            *a = f(*a, x.to_fast());
+        }
+    }
+    for (a, &x) in acc.iter_mut().zip(rest) {
+        *a = f(*a, x.to_fast());
+    }
+    pairwise(acc, combine)
+}
+
+/// Combine the accumulators pairwise.
+#[inline(always)]
+pub(crate) fn pairwise<A: Copy>(mut acc: [A; ACCUMULATORS], combine: impl Fn(A, A) -> A) -> A {
+    let mut n = ACCUMULATORS;
+    while n > 1 {
+        n /= 2;
+        for i in 0..n {
+            acc[i] = combine(acc[i], acc[i + n]);
+        }
+    }
+    acc[0]
+}
+
+#[inline(always)]
+fn zero<T: Element>() -> Output<T> {
+    Fast::from(T::Float::ZERO)
+}
+
+/// The sum of the elements of `xs`
+pub fn sum<T: Element>(xs: &[T]) -> Output<T> {
+    fold(xs, zero::<T>(), |acc, x| acc + x, |a, b| a + b)
+}
+
+/// The dot product of `xs` and `ys`
+///
+/// If the slices have different lengths, the longer one is truncated (like `zip`).
+pub fn dot<T: Element>(xs: &[T], ys: &[T]) -> Output<T> {
+    let n = xs.len().min(ys.len());
+    let (xs, ys) = (&xs[..n], &ys[..n]);
+    let mut acc = [zero::<T>(); ACCUMULATORS];
+    let mut x_chunks = xs.chunks_exact(ACCUMULATORS);
+    let mut y_chunks = ys.chunks_exact(ACCUMULATORS);
+    for (xc, yc) in (&mut x_chunks).zip(&mut y_chunks) {
+        for ((a, &x), &y) in acc.iter_mut().zip(xc).zip(yc) {
+            *a += x.to_fast() * y.to_fast();
+        }
+    }
+    let rest = x_chunks.remainder().iter().zip(y_chunks.remainder());
+    for (a, (&x, &y)) in acc.iter_mut().zip(rest) {
+        *a += x.to_fast() * y.to_fast();
+    }
+    pairwise(acc, |a, b| a + b)
+}
+
+/// The sum of the squares of the elements of `xs`
+pub fn sum_of_squares<T: Element>(xs: &[T]) -> Output<T> {
+    fold(xs, zero::<T>(), |acc, x| acc + x * x, |a, b| a + b)
+}
+
+/// The sum of the absolute values of the elements of `xs`
+pub fn l1_norm<T: Element>(xs: &[T]) -> Output<T> {
+    fold(xs, zero::<T>(), |acc, x| acc + x.abs(), |a, b| a + b)
+}
+
+/// The euclidean norm of `xs`
+///
+/// This is nightly only, since it needs the `sqrt` intrinsic.
+#[cfg(nightly)]
+pub fn l2_norm<T: Element>(xs: &[T]) -> Output<T> {
+    sum_of_squares(xs).sqrt()
+}
+
+/// The largest absolute value of the elements of `xs`, or zero if it is empty
+pub fn max_abs<T: Element>(xs: &[T]) -> Output<T> {
+    #[inline(always)]
+    fn max<F: FastFloat, Flags>(a: Fast<F, Flags>, b: Fast<F, Flags>) -> Fast<F, Flags> {
+        if b.0 > a.0 {
+            b
+        } else {
+            a
+        }
+    }
+    fold(xs, zero::<T>(), |acc, x| max(acc, x.abs()), max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::Strict;
+    use crate::FF64;
+
+    fn regular_sum(xs: impl IntoIterator<Item = f64>) -> f64 {
+        xs.into_iter().fold(0., |acc, x| acc + x)
+    }
+
+    fn data<const N: usize>() -> [f64; N] {
+        let mut xs = [0.; N];
+        for (i, x) in xs.iter_mut().enumerate() {
+            *x = (i as f64 * 0.37).fract() - 0.5;
+        }
+        xs
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-12 * b.abs().max(1.)
+    }
+
+    #[test]
+    fn reductions() {
+        let data = data::<64>();
+        // cover lengths with and without remainder
+        for n in [0, 1, 7, 8, 9, 31, 64] {
+            let xs = &data[..n];
+            let squares = || xs.iter().map(|x| x * x);
+            assert!(close(*sum(xs), regular_sum(xs.iter().copied())));
+            assert!(close(*dot(xs, xs), regular_sum(squares())));
+            assert!(close(*sum_of_squares(xs), regular_sum(squares())));
+            assert!(close(*l1_norm(xs), regular_sum(xs.iter().map(|x| x.abs()))));
+            let max = xs.iter().map(|x| x.abs()).fold(0., f64::max);
+            assert_eq!(*max_abs(xs), max);
+            #[cfg(nightly)]
+            assert!(close(*l2_norm(xs), regular_sum(squares()).sqrt()));
+        }
+    }
+
+    #[test]
+    fn fast_elements() {
+        let xs = data::<20>().map(FF64::from);
+        let ys = data::<20>().map(Fast::<f64, Strict>::from);
+        assert!(close(*sum(&xs), regular_sum(data::<20>())));
+        assert!(close(*sum(&ys), regular_sum(data::<20>())));
+        assert!(close(*dot(&xs, &xs[..10]), *sum_of_squares(&xs[..10])));
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let inf = f64::INFINITY;
+        assert_eq!(*sum(&[f64::MAX, f64::MAX]), inf);
+        assert_eq!(*sum(&[1., inf, 2.]), inf);
+        assert!(sum(&[1., f64::NAN, 2.]).is_nan());
+        assert!(sum(&[inf, -inf]).is_nan());
+        assert!(dot(&[inf, 1.], &[0., 1.]).is_nan());
+        assert_eq!(*sum_of_squares(&[1e300; 9]), inf);
+        assert_eq!(*l1_norm(&[-inf, 1.]), inf);
+        assert_eq!(*max_abs(&[1., -inf, 2.]), inf);
+    }
+}
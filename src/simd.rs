@@ -0,0 +1,314 @@
+//! Explicit SIMD lanes: `Fast<Simd<F, N>>`.
+//!
+//! Requires the `simd` crate feature and a nightly compiler.
+//!
+//! There are no fast-math intrinsics for vectors, so the lane-wise operators are the regular
+//! vector operations, strict whatever the flag set. The flags come into play in the
+//! horizontal reductions, which may reassociate (and so use a tree reduction) unless the flag
+//! set is [`Strict`], and in the `sanitize` feature, which checks every lane of the operands
+//! and result like for the scalar operators.
+//!
+//! [`Strict`]: crate::flags::Strict
+
+use crate::flags::FlagSet;
+use crate::{Fast, FastFloat};
+use std::intrinsics::simd::{simd_reduce_add_unordered, simd_reduce_mul_unordered};
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::simd::num::SimdFloat;
+use std::simd::{Simd, SimdElement};
+use std::slice::ChunksExact;
+
+/// “fast-math” vector of `N` `f32` lanes
+pub type FF32x<const N: usize> = Fast<Simd<f32, N>>;
+/// “fast-math” vector of `N` `f64` lanes
+pub type FF64x<const N: usize> = Fast<Simd<f64, N>>;
+
+/// Check every lane of `value`, see the `sanitize` feature
+#[cfg(feature = "sanitize")]
+#[track_caller]
+fn sanitize<F, const N: usize, Flags>(op: &'static str, what: &'static str, value: Simd<F, N>)
+where
+    F: FastFloat + SimdElement,
+    Flags: FlagSet,
+{
+    for &lane in value.as_array() {
+        crate::sanitize::<_, Flags>(op, what, lane);
+    }
+}
+
+macro_rules! impl_simd_op {
+    ($($name:ident, $method:ident;)*) => {
+        $(
+        // Fast<Simd> + Simd
+        impl<F, const N: usize, Flags> $name<Simd<F, N>> for Fast<Simd<F, N>, Flags>
+        where
+            F: FastFloat + SimdElement,
+            Simd<F, N>: $name<Output = Simd<F, N>>,
+            Flags: FlagSet,
+        {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: Simd<F, N>) -> Self::Output {
+                #[cfg(feature = "sanitize")]
+                {
+                    sanitize::<_, N, Flags>(stringify!($method), "left operand", self.0);
+                    sanitize::<_, N, Flags>(stringify!($method), "right operand", rhs);
+                }
+                let result = self.0.$method(rhs);
+                #[cfg(feature = "sanitize")]
+                sanitize::<_, N, Flags>(stringify!($method), "result", result);
+                Fast(result, PhantomData)
+            }
+        }
+
+        // Fast<Simd> + Fast<Simd>
+        impl<F, const N: usize, Flags> $name for Fast<Simd<F, N>, Flags>
+        where
+            F: FastFloat + SimdElement,
+            Simd<F, N>: $name<Output = Simd<F, N>>,
+            Flags: FlagSet,
+        {
+            type Output = Self;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: Self) -> Self::Output {
+                self.$method(rhs.0)
+            }
+        }
+
+        // Simd + Fast<Simd>
+        impl<F, const N: usize, Flags> $name<Fast<Simd<F, N>, Flags>> for Simd<F, N>
+        where
+            F: FastFloat + SimdElement,
+            Simd<F, N>: $name<Output = Simd<F, N>>,
+            Flags: FlagSet,
+        {
+            type Output = Fast<Simd<F, N>, Flags>;
+            #[inline(always)]
+            #[cfg_attr(feature = "sanitize", track_caller)]
+            fn $method(self, rhs: Fast<Simd<F, N>, Flags>) -> Self::Output {
+                Fast(self, PhantomData).$method(rhs.0)
+            }
+        }
+
+        // The same with references to either operand
+        impl_ref_op!([] $name, $method; [F, const N: usize, Flags] Fast<Simd<F, N>, Flags>, Simd<F, N>
+            where F: FastFloat + SimdElement, Simd<F, N>: $name<Output = Simd<F, N>>, Flags: FlagSet);
+        impl_ref_op!([] $name, $method; [F, const N: usize, Flags] Fast<Simd<F, N>, Flags>, Fast<Simd<F, N>, Flags>
+            where F: FastFloat + SimdElement, Simd<F, N>: $name<Output = Simd<F, N>>, Flags: FlagSet);
+        impl_ref_op!([] $name, $method; [F, const N: usize, Flags] Simd<F, N>, Fast<Simd<F, N>, Flags>
+            where F: FastFloat + SimdElement, Simd<F, N>: $name<Output = Simd<F, N>>, Flags: FlagSet);
+        )*
+    }
+}
+
+impl_simd_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+}
+
+impl<F, const N: usize, Flags> Fast<Simd<F, N>, Flags>
+where
+    F: FastFloat + SimdElement,
+    Simd<F, N>: SimdFloat<Scalar = F>,
+    Flags: FlagSet,
+{
+    /// A vector with all lanes set to `value`
+    #[inline(always)]
+    pub fn splat(value: Fast<F, Flags>) -> Self {
+        Fast(Simd::splat(value.0), PhantomData)
+    }
+
+    /// Load the first `N` elements of `xs`; missing lanes are set to zero
+    #[inline]
+    pub fn load_or_default(xs: &[Fast<F, Flags>]) -> Self {
+        Self::load_or(xs, Fast(Simd::splat(F::ZERO), PhantomData))
+    }
+
+    /// Load the first `N` elements of `xs`; missing lanes are taken from `or`
+    #[inline]
+    pub fn load_or(xs: &[Fast<F, Flags>], or: Self) -> Self {
+        Fast(Simd::load_or(Fast::slice_as_inner(xs), or.0), PhantomData)
+    }
+
+    /// Store the lanes into the first `N` elements of `xs`; lanes past the end of `xs` are
+    /// dropped
+    #[inline]
+    pub fn store(self, xs: &mut [Fast<F, Flags>]) {
+        for (x, &lane) in xs.iter_mut().zip(self.0.as_array()) {
+            *x = Fast(lane, PhantomData);
+        }
+    }
+
+    /// The sum of the lanes
+    #[inline(always)]
+    pub fn reduce_sum(self) -> Fast<F, Flags> {
+        if Flags::REASSOCIATE {
+            Fast(unsafe { simd_reduce_add_unordered(self.0) }, PhantomData)
+        } else {
+            Fast(self.0.reduce_sum(), PhantomData)
+        }
+    }
+
+    /// The product of the lanes
+    #[inline(always)]
+    pub fn reduce_product(self) -> Fast<F, Flags> {
+        if Flags::REASSOCIATE {
+            Fast(unsafe { simd_reduce_mul_unordered(self.0) }, PhantomData)
+        } else {
+            Fast(self.0.reduce_product(), PhantomData)
+        }
+    }
+
+    /// The largest lane
+    ///
+    /// Unlike `SimdFloat::reduce_max` this has no special case for NaN.
+    #[inline(always)]
+    pub fn reduce_max(self) -> Fast<F, Flags> {
+        let lanes = self.0.to_array();
+        let max = lanes[1..]
+            .iter()
+            .fold(lanes[0], |m, &x| if x > m { x } else { m });
+        Fast(max, PhantomData)
+    }
+}
+
+/// Split `xs` into full vectors of `N` lanes and the remaining elements
+///
+/// ```
+/// # #![feature(portable_simd)]
+/// use fast_floats::simd::{split_lanes, FF64x};
+/// use fast_floats::FF64;
+///
+/// let xs = [1., 2., 3., 4., 5.].map(FF64::from);
+/// let (lanes, rest) = split_lanes::<_, 2, _>(&xs);
+/// let sum = lanes.sum::<FF64x<2>>().reduce_sum() + rest.iter().sum::<FF64>();
+/// assert_eq!(*sum, 15.);
+/// ```
+pub fn split_lanes<F, const N: usize, Flags>(
+    xs: &[Fast<F, Flags>],
+) -> (Lanes<'_, F, N, Flags>, &[Fast<F, Flags>])
+where
+    F: FastFloat + SimdElement,
+{
+    let chunks = xs.chunks_exact(N);
+    let rest = chunks.remainder();
+    (Lanes { chunks }, rest)
+}
+
+/// Iterator over the full vectors of a slice, see [`split_lanes`]
+pub struct Lanes<'a, F, const N: usize, Flags> {
+    chunks: ChunksExact<'a, Fast<F, Flags>>,
+}
+
+impl<F, const N: usize, Flags> Iterator for Lanes<'_, F, N, Flags>
+where
+    F: FastFloat + SimdElement,
+{
+    type Item = Fast<Simd<F, N>, Flags>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        Some(Fast(
+            Simd::from_slice(Fast::slice_as_inner(chunk)),
+            PhantomData,
+        ))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<F, const N: usize, Flags> std::iter::Sum for Fast<Simd<F, N>, Flags>
+where
+    F: FastFloat + SimdElement,
+    Simd<F, N>: Add<Output = Simd<F, N>>,
+    Flags: FlagSet,
+{
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fast(Simd::splat(F::ZERO), PhantomData), |acc, x| acc + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::Strict;
+    use crate::{FF32, FF64};
+    use std::simd::f64x4;
+
+    #[test]
+    #[allow(clippy::op_ref)] // the reference impls are under test
+    fn each_op() {
+        let x = FF64x::<4>::from(f64x4::from_array([1., 2., 3., 4.]));
+        let y = f64x4::splat(2.);
+        assert_eq!(*(x + y), f64x4::from_array([3., 4., 5., 6.]));
+        assert_eq!(*(x - Fast::from(y)), f64x4::from_array([-1., 0., 1., 2.]));
+        assert_eq!(*(y * x), f64x4::from_array([2., 4., 6., 8.]));
+        assert_eq!(*(x / y), f64x4::from_array([0.5, 1., 1.5, 2.]));
+        assert_eq!(*(x % y), f64x4::from_array([1., 0., 1., 0.]));
+        assert_eq!(&x + &x, x + x);
+        assert_eq!(x * &y, x * y);
+        assert_eq!(&y - x, y - x);
+
+        let mut z = x;
+        z += y;
+        z *= x;
+        assert_eq!(*z, f64x4::from_array([3., 8., 15., 24.]));
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    #[should_panic(expected = "result of `div` is invalid: value is infinite")]
+    fn sanitize_lanes() {
+        let x = FF64x::<4>::from(f64x4::from_array([1., 2., 3., 4.]));
+        let _ = x / f64x4::from_array([1., 1., 0., 1.]);
+    }
+
+    #[test]
+    fn strict_lanes() {
+        let x = Fast::<_, Strict>::from(f64x4::splat(1.));
+        let y = x / f64x4::from_array([1., 0., -0., f64::NAN]);
+        assert_eq!(y[..3], [1., f64::INFINITY, f64::NEG_INFINITY]);
+        assert!(y[3].is_nan());
+    }
+
+    #[test]
+    fn reductions() {
+        let x = FF64x::<4>::from(f64x4::from_array([1., 4., 3., 2.]));
+        assert_eq!(x.reduce_sum(), FF64::from(10.));
+        assert_eq!(x.reduce_product(), FF64::from(24.));
+        assert_eq!(x.reduce_max(), FF64::from(4.));
+
+        let x = Fast::<_, Strict>::from(f64x4::from_array([1., 4., 3., 2.]));
+        assert_eq!(*x.reduce_sum(), 10.);
+        assert_eq!(*x.reduce_product(), 24.);
+    }
+
+    #[test]
+    fn load_store() {
+        let xs = [1., 2., 3.].map(FF32::from);
+        let v = FF32x::<4>::load_or_default(&xs);
+        assert_eq!(v.reduce_sum(), FF32::from(6.));
+        let v = FF32x::<4>::load_or(&xs, FF32x::splat(FF32::from(1.)));
+        assert_eq!(v.reduce_product(), FF32::from(6.));
+
+        let mut ys = [FF32::from(0.); 3];
+        (v + v).store(&mut ys);
+        assert_eq!(ys, [2., 4., 6.].map(FF32::from));
+
+        let xs = [1., 2., 3., 4., 5., 6., 7.].map(FF32::from);
+        let (lanes, rest) = split_lanes::<_, 4, _>(&xs);
+        assert_eq!(lanes.sum::<FF32x<4>>().reduce_sum(), FF32::from(10.));
+        assert_eq!(rest, &xs[4..]);
+    }
+}